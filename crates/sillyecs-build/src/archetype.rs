@@ -1,5 +1,6 @@
 use crate::Name;
 use crate::component::{Component, ComponentId, ComponentRef};
+use crate::naming::Naming;
 use core::ops::Deref;
 use serde::{Deserialize, Deserializer, Serialize};
 
@@ -10,9 +11,23 @@ pub struct Archetype {
     pub name: ArchetypeName,
     #[serde(default)]
     pub description: Option<String>,
+    /// The components of the archetype. After [`Ecs::expand_archetype_extensions`](crate::ecs::Ecs::expand_archetype_extensions),
+    /// this also holds the components of the extended archetype, in front of its own.
+    #[serde(default)]
     pub components: Vec<ComponentRef>,
+    /// An optional archetype whose components are merged into this one.
+    #[serde(default, skip_serializing)]
+    pub extends: Option<ArchetypeRef>,
     #[serde(default, skip_serializing)]
     pub promotions: Vec<ArchetypeRef>,
+    /// Whether the entity bundles of this archetype implement `PartialEq`. Requires the data
+    /// types of all its components to implement `PartialEq`.
+    #[serde(default)]
+    pub comparable: bool,
+    /// The maximum number of entities of this archetype per world, enforced by its `try_spawn`
+    /// function. Unlimited if omitted.
+    #[serde(default)]
+    pub max_entities: Option<usize>,
 
     /// The promotion information. Available after a call to [`Archetype::finish`](Archetype::finish).
     #[serde(skip_deserializing, default)]
@@ -25,6 +40,28 @@ pub struct Archetype {
     /// The number of components. Available after a call to [`Archetype::finish`](Archetype::finish).
     #[serde(skip_deserializing, default)]
     pub component_count: usize,
+
+    /// The components as a bit mask with bit `id - 1` set for each component ID, least significant
+    /// bit first. Available after a call to [`Archetype::finish`](Archetype::finish).
+    #[serde(skip_deserializing, default)]
+    pub component_mask: Vec<u8>,
+
+    /// The archetypes holding exactly one component more than this one. Available after a call to [`Archetype::finish`](Archetype::finish).
+    #[serde(skip_deserializing, default)]
+    pub add_edges: Vec<ArchetypeEdge>,
+
+    /// The archetypes holding exactly one component less than this one. Available after a call to [`Archetype::finish`](Archetype::finish).
+    #[serde(skip_deserializing, default)]
+    pub remove_edges: Vec<ArchetypeEdge>,
+}
+
+/// A structural move between two archetypes that differ in a single component.
+#[derive(Debug, Clone, Serialize)]
+pub struct ArchetypeEdge {
+    /// The component added or removed by the move.
+    pub component: ComponentRef,
+    /// The archetype the entity ends up in.
+    pub target: ArchetypeRef,
 }
 
 #[derive(Debug, Clone, Serialize)]
@@ -43,21 +80,76 @@ impl Archetype {
             let id = components
                 .iter()
                 .find(|c| c.name.type_name == component.type_name)
-                .expect("Component not found")
+                .unwrap_or_else(|| {
+                    panic!(
+                        "Component `{}` referenced by archetype `{}` is not defined",
+                        component.type_name_raw, self.name.type_name_raw
+                    )
+                })
                 .id;
             ids.push(id);
         }
         ids.sort_unstable();
+        self.component_mask = vec![0; components.len().div_ceil(8)];
+        for id in &ids {
+            let bit = id.0 as usize - 1;
+            self.component_mask[bit / 8] |= 1 << (bit % 8);
+        }
         self.component_count = ids.len();
         self.component_ids = ids;
 
+        // Find the archetypes reachable by adding or removing a single component.
+        assert!(self.add_edges.is_empty() && self.remove_edges.is_empty());
+        for other in archetypes {
+            if other.components.len() == self.components.len() + 1
+                && self.components.iter().all(|c| other.components.contains(c))
+            {
+                let added = other
+                    .components
+                    .iter()
+                    .find(|c| !self.components.contains(c))
+                    .unwrap_or_else(|| {
+                        panic!(
+                            "Archetype `{}` must have one component more than `{}`",
+                            other.name.type_name_raw, self.name.type_name_raw
+                        )
+                    });
+                self.add_edges.push(ArchetypeEdge {
+                    component: added.clone(),
+                    target: other.name.clone(),
+                });
+            } else if other.components.len() + 1 == self.components.len()
+                && other.components.iter().all(|c| self.components.contains(c))
+            {
+                let removed = self
+                    .components
+                    .iter()
+                    .find(|c| !other.components.contains(c))
+                    .unwrap_or_else(|| {
+                        panic!(
+                            "Archetype `{}` must have one component less than `{}`",
+                            other.name.type_name_raw, self.name.type_name_raw
+                        )
+                    });
+                self.remove_edges.push(ArchetypeEdge {
+                    component: removed.clone(),
+                    target: other.name.clone(),
+                });
+            }
+        }
+
         // Process promotions.
         assert!(self.promotion_infos.is_empty());
         for promotion in &self.promotions {
             let target = archetypes
                 .iter()
                 .find(|a| a.name.eq(promotion))
-                .expect("Promotion target not found");
+                .unwrap_or_else(|| {
+                    panic!(
+                        "Promotion target `{}` of archetype `{}` is not defined",
+                        promotion.type_name_raw, self.name.type_name_raw
+                    )
+                });
             let mut components_to_pass = Vec::new();
             for component in &self.components {
                 if target.components.contains(component) {
@@ -102,6 +194,43 @@ impl<'de> Deserialize<'de> for ArchetypeName {
         D: Deserializer<'de>,
     {
         let type_name = String::deserialize(deserializer)?;
-        Ok(Self(Name::new(type_name, "Archetype")))
+        Ok(Self(Name::new(
+            type_name,
+            &Naming::suffix(|naming| &naming.archetype_suffix),
+        )))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn particle() -> Archetype {
+        serde_yaml::from_str(
+            r#"
+name: Particle
+components: [Position]
+promotions: [LivingParticle]
+"#,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "Component `Position` referenced by archetype `Particle` is not defined"
+    )]
+    fn finish_names_undefined_component_and_archetype() {
+        particle().finish(&[], &[]);
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "Promotion target `LivingParticle` of archetype `Particle` is not defined"
+    )]
+    fn finish_names_undefined_promotion_target() {
+        let mut position: Component = serde_yaml::from_str("name: Position").unwrap();
+        position.id = ComponentId(1);
+        particle().finish(&[position], &[]);
     }
 }
@@ -0,0 +1,164 @@
+//! A serializable snapshot of a finished ECS, e.g. to cache it between builds.
+//!
+//! The snapshot stores the ECS in the form the templates consume, so rendering it again produces
+//! the same code without repeating validation and scheduling.
+
+use crate::ecs::{Ecs, EcsError};
+use crate::schema::schema_json;
+use minijinja::value::ValueKind;
+use minijinja::{Error, ErrorKind, Value};
+use serde::ser::SerializeMap;
+use serde::{Deserialize, Serialize, Serializer};
+
+/// A finished ECS description that can be stored and reloaded to skip recomputation.
+///
+/// Obtain it from [`EcsCode::finished`](crate::EcsCode::finished), store it with
+/// [`FinishedEcs::to_json`] and render it again with
+/// [`EcsCode::from_finished`](crate::EcsCode::from_finished) while
+/// [`FinishedEcs::is_current_for`] holds for the ECS source.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct FinishedEcs {
+    /// The version of `sillyecs-build` that finished the ECS.
+    generator: String,
+    /// The FNV-1a hash of the YAML source of the ECS.
+    source_hash: u64,
+    /// Whether the `single-threaded` feature forced single-threaded code when finishing the ECS.
+    single_threaded_feature: bool,
+    /// The finished ECS as consumed by the templates.
+    ecs: CachedValue,
+    /// The JSON schema of the ECS.
+    schema: String,
+    /// The non-fatal diagnostics collected while finishing the ECS.
+    diagnostics: Vec<String>,
+}
+
+impl FinishedEcs {
+    pub(crate) fn new(ecs: &Ecs, source: &[u8]) -> Result<Self, EcsError> {
+        Ok(Self {
+            generator: env!("CARGO_PKG_VERSION").to_string(),
+            source_hash: fnv1a(source),
+            single_threaded_feature: cfg!(feature = "single-threaded"),
+            ecs: CachedValue::from_value(&Value::from_serialize(ecs))?,
+            schema: schema_json(ecs),
+            diagnostics: ecs.diagnostics.clone(),
+        })
+    }
+
+    /// Indicates whether this ECS was finished from the specified YAML source by this version
+    /// of `sillyecs-build` with the same code-affecting features enabled, i.e. whether it can be
+    /// used instead of generating from `source`.
+    pub fn is_current_for(&self, source: &str) -> bool {
+        self.generator == env!("CARGO_PKG_VERSION")
+            && self.source_hash == fnv1a(source.as_bytes())
+            && self.single_threaded_feature == cfg!(feature = "single-threaded")
+    }
+
+    /// Serializes the finished ECS to JSON.
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(self).expect("The cache only uses string map keys")
+    }
+
+    /// Deserializes a finished ECS previously serialized with [`FinishedEcs::to_json`].
+    pub fn from_json(json: &str) -> Result<Self, EcsError> {
+        Ok(serde_json::from_str(json)?)
+    }
+
+    /// Returns the template context of the ECS.
+    pub(crate) fn template_value(&self) -> Value {
+        Value::from_serialize(TemplateValue(&self.ecs))
+    }
+
+    pub(crate) fn schema(&self) -> &str {
+        &self.schema
+    }
+
+    pub(crate) fn diagnostics(&self) -> &[String] {
+        &self.diagnostics
+    }
+}
+
+/// A template value in a form that survives a round trip through JSON, including map keys that
+/// are not strings and the exact values of floats.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+enum CachedValue {
+    #[default]
+    None,
+    Bool(bool),
+    I64(i64),
+    U64(u64),
+    F64(f64),
+    String(String),
+    Seq(Vec<CachedValue>),
+    Map(Vec<(CachedValue, CachedValue)>),
+}
+
+impl CachedValue {
+    fn from_value(value: &Value) -> Result<Self, Error> {
+        Ok(match value.kind() {
+            ValueKind::None | ValueKind::Undefined => Self::None,
+            ValueKind::Bool => Self::Bool(value.is_true()),
+            ValueKind::Number if value.is_integer() => match value.as_i64() {
+                Some(number) => Self::I64(number),
+                None => Self::U64(u64::try_from(value.clone())?),
+            },
+            ValueKind::Number => Self::F64(f64::try_from(value.clone())?),
+            ValueKind::String => Self::String(value.to_string()),
+            ValueKind::Seq => Self::Seq(
+                value
+                    .try_iter()?
+                    .map(|item| Self::from_value(&item))
+                    .collect::<Result<_, _>>()?,
+            ),
+            ValueKind::Map => Self::Map(
+                value
+                    .try_iter()?
+                    .map(|key| {
+                        let item = value.get_item(&key)?;
+                        Ok((Self::from_value(&key)?, Self::from_value(&item)?))
+                    })
+                    .collect::<Result<_, Error>>()?,
+            ),
+            kind => {
+                return Err(Error::new(
+                    ErrorKind::InvalidOperation,
+                    format!("cannot cache a template value of kind {kind}"),
+                ));
+            }
+        })
+    }
+}
+
+/// Serializes a [`CachedValue`] as the value it was created from.
+struct TemplateValue<'a>(&'a CachedValue);
+
+impl Serialize for TemplateValue<'_> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        match self.0 {
+            CachedValue::None => serializer.serialize_none(),
+            CachedValue::Bool(value) => serializer.serialize_bool(*value),
+            CachedValue::I64(value) => serializer.serialize_i64(*value),
+            CachedValue::U64(value) => serializer.serialize_u64(*value),
+            CachedValue::F64(value) => serializer.serialize_f64(*value),
+            CachedValue::String(value) => serializer.serialize_str(value),
+            CachedValue::Seq(items) => serializer.collect_seq(items.iter().map(TemplateValue)),
+            CachedValue::Map(entries) => {
+                let mut map = serializer.serialize_map(Some(entries.len()))?;
+                for (key, value) in entries {
+                    map.serialize_entry(&TemplateValue(key), &TemplateValue(value))?;
+                }
+                map.end()
+            }
+        }
+    }
+}
+
+/// Computes the 64-bit FNV-1a hash, which unlike the standard library hasher is stable across
+/// Rust versions.
+fn fnv1a(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf2_9ce4_8422_2325, |hash, &byte| {
+        (hash ^ u64::from(byte)).wrapping_mul(0x0000_0100_0000_01b3)
+    })
+}
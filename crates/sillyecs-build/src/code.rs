@@ -1,8 +1,10 @@
+use crate::cache::FinishedEcs;
 use crate::ecs::{Ecs, EcsError};
+use crate::naming::Naming;
 use crate::{doc_lines_filter, snake_case_filter};
 use minijinja::{Environment, context};
 use std::fs::File;
-use std::io::{BufReader, Write};
+use std::io::{BufReader, Read, Write};
 use std::{env, io};
 
 #[derive(Default)]
@@ -11,6 +13,18 @@ pub struct EcsCode {
     pub archetypes: String,
     pub systems: String,
     pub world: String,
+    /// The finished ECS the code was generated from.
+    finished: FinishedEcs,
+}
+
+/// The destinations of the generated code, one per template; see
+/// [`EcsCode::render_to_writers`].
+#[derive(Debug, Default)]
+pub struct CodeWriters<W> {
+    pub components: W,
+    pub archetypes: W,
+    pub systems: W,
+    pub world: W,
 }
 
 #[derive(thiserror::Error, Debug)]
@@ -21,15 +35,24 @@ pub enum WriteCodeError {
     FailedToOpenFile(String, io::Error),
     #[error("Failed to write to file {0}: {1}")]
     FailedToWriteFile(String, io::Error),
+    #[error("Failed to read file {0}: {1}")]
+    FailedToReadFile(String, io::Error),
 }
 
 impl EcsCode {
-    pub fn generate<R>(reader: BufReader<R>) -> Result<EcsCode, EcsError>
+    pub fn generate<R>(mut reader: BufReader<R>) -> Result<EcsCode, EcsError>
     where
         R: io::Read,
     {
-        let mut ecs: Ecs = serde_yaml::from_reader(reader).expect("Failed to deserialize ecs.yaml");
+        let mut source = Vec::new();
+        reader.read_to_end(&mut source)?;
+
+        let naming = Naming::from_source(&source)?;
+        let mut ecs: Ecs = naming.apply(|| serde_yaml::from_slice(&source))?;
+        ecs.remove_disabled_systems();
+        ecs.expand_archetype_extensions()?;
         ecs.ensure_state_consistency()?;
+        ecs.ensure_phase_order()?;
         ecs.ensure_component_consistency()?;
         ecs.ensure_distinct_archetype_components()?;
         ecs.ensure_system_consistency()?;
@@ -37,6 +60,40 @@ impl EcsCode {
         ecs.ensure_world_consistency()?;
         ecs.finish()?;
 
+        Self::from_finished(&FinishedEcs::new(&ecs, &source)?)
+    }
+
+    /// Generates the code from an ECS finished earlier, e.g. one loaded from a build cache,
+    /// without validating and scheduling it again.
+    pub fn from_finished(finished: &FinishedEcs) -> Result<EcsCode, EcsError> {
+        let mut writers = CodeWriters::<Vec<u8>>::default();
+        Self::render_to_writers(finished, &mut writers)?;
+
+        let into_code = |code| String::from_utf8(code).expect("templates render UTF-8");
+        Ok(EcsCode {
+            components: into_code(writers.components),
+            archetypes: into_code(writers.archetypes),
+            world: into_code(writers.world),
+            systems: into_code(writers.systems),
+            finished: finished.clone(),
+        })
+    }
+
+    /// Renders the code of an ECS finished earlier directly into the given writers, one per
+    /// template.
+    ///
+    /// Unlike [`EcsCode::from_finished`], the code is streamed instead of being held in memory,
+    /// which keeps the peak memory low for very large ECS definitions. Wrap files in a
+    /// [`BufWriter`](io::BufWriter), as templates are written in many small pieces.
+    pub fn render_to_writers<W>(
+        finished: &FinishedEcs,
+        writers: &mut CodeWriters<W>,
+    ) -> Result<(), EcsError>
+    where
+        W: Write,
+    {
+        let ecs = finished.template_value();
+
         let mut env = Environment::new();
         env.add_filter("snake_case", snake_case_filter);
         env.add_filter("doc_lines", doc_lines_filter);
@@ -52,29 +109,49 @@ impl EcsCode {
         )?;
         env.add_template("systems", include_str!("../templates/systems.rs.jinja2"))?;
 
-        let world_code = env.get_template("world")?.render(context! {
-            ecs => ecs,
-        })?;
+        let templates = [
+            ("world", &mut writers.world),
+            ("components", &mut writers.components),
+            ("archetypes", &mut writers.archetypes),
+            ("systems", &mut writers.systems),
+        ];
+        for (name, writer) in templates {
+            env.get_template(name)?
+                .render_captured_to(context! { ecs => ecs }, writer)?;
+        }
+        Ok(())
+    }
 
-        let component_code = env.get_template("components")?.render(context! {
-            ecs => ecs,
-        })?;
+    /// Returns the finished ECS the code was generated from, e.g. to cache it between builds.
+    pub fn finished(&self) -> &FinishedEcs {
+        &self.finished
+    }
 
-        let archetype_code = env.get_template("archetypes")?.render(context! {
-            ecs => ecs,
-        })?;
+    /// Describes the finished ECS as pretty-printed JSON for external tools, such as schedule
+    /// visualizers and documentation generators.
+    ///
+    /// The JSON lists the components, archetypes, phases and systems (including their computed
+    /// IDs, affected archetypes and dependencies), as well as the scheduled batches of each
+    /// phase per world. The output is stable for a given ECS definition.
+    pub fn to_schema_json(&self) -> String {
+        self.finished.schema().to_string()
+    }
 
-        let system_code = env.get_template("systems")?.render(context! {
-            ecs => ecs,
-        })?;
+    /// Returns the non-fatal diagnostics collected during generation, e.g. when the scheduler
+    /// had to break a cycle between the data dependencies of systems.
+    ///
+    /// Generation does not print anything by itself; see [`EcsCode::emit_cargo_warnings`] to
+    /// surface the diagnostics from a build script.
+    pub fn diagnostics(&self) -> &[String] {
+        self.finished.diagnostics()
+    }
 
-        Ok(EcsCode {
-            components: component_code,
-            archetypes: archetype_code,
-            world: world_code,
-            systems: system_code,
-            ..EcsCode::default()
-        })
+    /// Prints each diagnostic as a `cargo:warning` line, so that Cargo shows it when called from a
+    /// build script.
+    pub fn emit_cargo_warnings(&self) {
+        for diagnostic in self.diagnostics() {
+            println!("cargo:warning={diagnostic}");
+        }
     }
 
     /// Writes generated code to multiple files in the output directory specified
@@ -146,6 +223,110 @@ impl EcsCode {
         Ok(())
     }
 
+    /// Concatenates the generated code into a single module, so that it can be included with
+    /// one `include!`.
+    ///
+    /// The sections follow the order in which they build on each other: components, archetypes,
+    /// systems, and finally the world. Each section is introduced by a comment naming it.
+    pub fn combined(&self) -> String {
+        let sections = [
+            ("Components", &self.components),
+            ("Archetypes", &self.archetypes),
+            ("Systems", &self.systems),
+            ("World", &self.world),
+        ];
+
+        let mut combined =
+            String::with_capacity(sections.iter().map(|(_, code)| code.len() + 64).sum());
+        for (name, code) in sections {
+            if !combined.is_empty() {
+                combined.push('\n');
+            }
+            combined.push_str("// ---- ");
+            combined.push_str(name);
+            combined.push_str(" ----\n\n");
+            combined.push_str(code);
+            if !code.ends_with('\n') {
+                combined.push('\n');
+            }
+        }
+        combined
+    }
+
+    /// Writes the generated code as a single module (see [`EcsCode::combined`]) to the
+    /// specified file in the output directory.
+    ///
+    /// # Parameters
+    /// - `out_dir`: The output directory path where the file will be written.
+    /// - `file_name`: The name of the file, e.g. `ecs_gen.rs`.
+    ///
+    /// # Errors
+    /// This function returns a `WriteCodeError` in the following cases:
+    /// - If the output directory is not a valid directory.
+    /// - If the file cannot be created or written.
+    pub fn write_combined_to<P>(&self, out_dir: P, file_name: &str) -> Result<(), WriteCodeError>
+    where
+        P: AsRef<str>,
+    {
+        let out_dir = out_dir.as_ref();
+
+        if !std::path::Path::new(out_dir).is_dir() {
+            return Err(WriteCodeError::InvalidDirectory(
+                out_dir.to_string(),
+                io::Error::new(
+                    io::ErrorKind::NotADirectory,
+                    "The specified path is not a directory",
+                ),
+            ));
+        }
+
+        Self::write_file(out_dir, file_name, &self.combined())
+    }
+
+    /// Compares the generated code against the files in the specified directory.
+    ///
+    /// This allows checking in the generated code and verifying in CI that it is up to date.
+    ///
+    /// # Parameters
+    /// - `dir`: The directory previously written to using [`EcsCode::write_files_to`](EcsCode::write_files_to).
+    ///
+    /// # Returns
+    /// - `Ok(true)` if all files exist and their contents match the generated code.
+    /// - `Ok(false)` if any file is missing or differs.
+    /// - `Err(WriteCodeError)` if the directory is invalid or a file cannot be read.
+    pub fn files_match<P>(&self, dir: P) -> Result<bool, WriteCodeError>
+    where
+        P: AsRef<str>,
+    {
+        let dir = dir.as_ref();
+
+        if !std::path::Path::new(dir).is_dir() {
+            return Err(WriteCodeError::InvalidDirectory(
+                dir.to_string(),
+                io::Error::new(
+                    io::ErrorKind::NotADirectory,
+                    "The specified path is not a directory",
+                ),
+            ));
+        }
+
+        Ok(
+            Self::file_matches(dir, "components_gen.rs", &self.components)?
+                && Self::file_matches(dir, "archetypes_gen.rs", &self.archetypes)?
+                && Self::file_matches(dir, "systems_gen.rs", &self.systems)?
+                && Self::file_matches(dir, "world_gen.rs", &self.world)?,
+        )
+    }
+
+    fn file_matches(dir: &str, file_name: &str, content: &str) -> Result<bool, WriteCodeError> {
+        let path = format!("{dir}/{file_name}");
+        match std::fs::read_to_string(&path) {
+            Ok(existing) => Ok(existing == content),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(false),
+            Err(e) => Err(WriteCodeError::FailedToReadFile(path, e)),
+        }
+    }
+
     fn write_file(out_dir: &str, file_name: &str, content: &str) -> Result<(), WriteCodeError> {
         let path = format!("{out_dir}/{file_name}");
         let mut file =
@@ -1,5 +1,6 @@
 use crate::Name;
 use crate::archetype::{Archetype, ArchetypeId, ArchetypeRef};
+use crate::naming::Naming;
 use crate::system::{System, SystemId, SystemName};
 use serde::{Deserialize, Deserializer, Serialize};
 use std::ops::Deref;
@@ -11,6 +12,30 @@ pub struct Component {
     pub name: ComponentName,
     #[serde(default)]
     pub description: Option<String>,
+    /// The version of the component's data layout, e.g. for migrating save files.
+    #[serde(default)]
+    pub version: u32,
+    /// An existing Rust type to store instead of a generated component wrapper, e.g. a generic
+    /// type such as `Buffer<16>`. The type is referenced verbatim and never defined.
+    #[serde(default)]
+    pub rust_type: Option<String>,
+    /// The fields of the component's data type, reflected as `FIELDS` on the component type,
+    /// e.g. for editor property panels. The data type itself is still written by hand.
+    #[serde(default)]
+    pub fields: Vec<ComponentField>,
+    /// The minimum alignment in bytes of the component's storage, e.g. `32` for AVX. Must be a
+    /// power of two. Components declaring it are stored in a `sillyecs::AlignedVec`.
+    #[serde(default)]
+    pub align: Option<usize>,
+    /// Whether the component is knowingly large. Other components must not exceed the
+    /// `max_component_size` of the ECS, since archetypes move them on every swap-remove; huge
+    /// data should rather be boxed.
+    #[serde(default)]
+    pub large: bool,
+    /// Components that every archetype containing this component must contain as well, e.g.
+    /// `Position` for `Velocity`.
+    #[serde(default, skip_serializing)]
+    pub requires: Vec<ComponentRef>,
 
     /// The archetypes this system operates on. Available after a call to [`Component::finish`](Component::finish).
     #[serde(skip_deserializing, default)]
@@ -21,6 +46,9 @@ pub struct Component {
     /// The number of affected archetypes. Available after a call to [`Component::finish`](Component::finish).
     #[serde(skip_deserializing, default)]
     pub affected_archetype_count: usize,
+    /// Whether the component is part of a comparable archetype and thus implements `PartialEq`. Available after a call to [`Component::finish`](Component::finish).
+    #[serde(skip_deserializing, default)]
+    pub comparable: bool,
 
     /// The systems this system operates on. Available after a call to [`Component::finish`](Component::finish).
     #[serde(skip_deserializing, default)]
@@ -35,19 +63,61 @@ pub struct Component {
 
 pub type ComponentRef = ComponentName;
 
+/// A field of a component's data type as declared for reflection.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ComponentField {
+    /// The name of the field.
+    pub name: String,
+    /// The type of the field as written in Rust, e.g. `f32`.
+    #[serde(rename = "type")]
+    pub type_name: String,
+}
+
 #[derive(Debug, Default, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize)]
 #[serde(transparent)]
 pub struct ComponentId(pub(crate) u64);
 
 #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize)]
-#[serde(transparent)]
-pub struct ComponentName(pub(crate) Name);
+pub struct ComponentName {
+    #[serde(flatten)]
+    name: Name,
+    /// The type holding the data of the component, e.g. `PositionData`.
+    data: String,
+    /// The alignment of the component's storage, if the component declares one.
+    align: Option<usize>,
+}
+
+impl ComponentName {
+    pub(crate) fn new(type_name: String) -> Self {
+        let name = Name::new(
+            type_name,
+            &Naming::suffix(|naming| &naming.component_suffix),
+        );
+        let data = format!("{}Data", name.type_name_raw);
+        Self {
+            name,
+            data,
+            align: None,
+        }
+    }
+
+    /// Refers to the component through the specified Rust type, which then also holds its data.
+    pub(crate) fn use_rust_type(&mut self, rust_type: &str) {
+        self.name.type_name = rust_type.to_string();
+        self.data = rust_type.to_string();
+    }
+
+    /// Stores the component with the specified alignment.
+    pub(crate) fn use_alignment(&mut self, align: usize) {
+        self.align = Some(align);
+    }
+}
 
 impl Deref for ComponentName {
     type Target = Name;
 
     fn deref(&self) -> &Self::Target {
-        &self.0
+        &self.name
     }
 }
 
@@ -57,7 +127,7 @@ impl<'de> Deserialize<'de> for ComponentName {
         D: Deserializer<'de>,
     {
         let type_name = String::deserialize(deserializer)?;
-        Ok(Self(Name::new(type_name, "Component")))
+        Ok(Self::new(type_name))
     }
 }
 
@@ -68,6 +138,7 @@ impl Component {
         for archetype in archetypes {
             if archetype.components.iter().any(|c| c.eq(&self.name)) {
                 ids_and_names.push((archetype.id, archetype.name.clone()));
+                self.comparable |= archetype.comparable;
             }
         }
         ids_and_names.sort_unstable_by_key(|entry| entry.0);
@@ -79,9 +150,13 @@ impl Component {
         // Scan systems
         let mut ids_and_names = Vec::new();
         for system in systems {
-            if system.inputs.iter().any(|c| c.eq(&self.name)) {
-                ids_and_names.push((system.id, system.name.clone()));
-            } else if system.outputs.iter().any(|c| c.eq(&self.name)) {
+            if system
+                .inputs
+                .iter()
+                .chain(system.outputs.iter().map(|output| &output.component))
+                .chain(&system.maybe)
+                .any(|c| c.eq(&self.name))
+            {
                 ids_and_names.push((system.id, system.name.clone()));
             }
         }
@@ -1,13 +1,15 @@
-use crate::archetype::{Archetype, ArchetypeId};
-use crate::component::{Component, ComponentId};
+use crate::archetype::{Archetype, ArchetypeId, ArchetypeName};
+use crate::component::{Component, ComponentId, ComponentName, ComponentRef};
+use crate::naming::Naming;
 use crate::state::State;
-use crate::system::{System, SystemId, SystemPhase};
+use crate::system::{FixedTiming, System, SystemId, SystemName, SystemPhase};
+use crate::system_scheduler::resolve_set_ordering;
 use crate::view::View;
-use crate::world::{World, WorldId};
+use crate::world::{CommandQueueName, World, WorldId};
 use serde::{Deserialize, Serialize};
 use std::collections::{HashMap, HashSet};
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Default, Serialize, Deserialize)]
 pub struct Ecs {
     /// The components.
     pub components: Vec<Component>,
@@ -21,10 +23,18 @@ pub struct Ecs {
     /// Indicates whether any phase os conditional.
     #[serde(default, skip_deserializing)]
     pub any_phase_on_request: bool,
-    /// The systems.
+    /// The systems. May be omitted for an ECS that only stores entities.
+    #[serde(default)]
     pub systems: Vec<System>,
+    /// Orderings between system sets as `[before, after]` pairs: every system of the first set
+    /// runs before every system of the second set in the same phase.
+    #[serde(default)]
+    pub set_ordering: Vec<(String, String)>,
     /// The worlds.
     pub worlds: Vec<World>,
+    /// The distinct names of the command queues declared by the worlds, in order of declaration.
+    #[serde(default, skip_deserializing)]
+    pub command_queues: Vec<CommandQueueName>,
     /// The user states.
     #[serde(default)]
     pub states: Vec<State>,
@@ -34,11 +44,37 @@ pub struct Ecs {
     /// Allow the generation of unsafe code.
     #[serde(default)]
     pub allow_unsafe: bool,
+    /// The generic type storing the component values of every archetype, e.g. `Vec` or a path to
+    /// a type implementing `sillyecs::ComponentStorage`. Defaults to `Vec`.
+    #[serde(default)]
+    pub storage: Option<String>,
+    /// Generate code for single-threaded targets, such as `wasm32-unknown-unknown`: components,
+    /// systems and user commands need not be `Send + Sync`, and the parallel phase variants run
+    /// their systems serially. Always enabled by the `single-threaded` feature.
+    #[serde(default)]
+    pub single_threaded: bool,
+    /// The maximum size in bytes of a component not marked as `large`, checked at compile time.
+    /// Defaults to 256 bytes.
+    #[serde(default = "default_max_component_size")]
+    pub max_component_size: usize,
+    /// The suffixes of the generated type names.
+    #[serde(default)]
+    pub naming: Naming,
+    /// Non-fatal messages collected while finishing the ECS, e.g. about scheduling conflicts
+    /// that had to be resolved. Available after a call to [`Ecs::finish`](Ecs::finish).
+    #[serde(skip)]
+    pub diagnostics: Vec<String>,
+    /// The names of the systems removed by [`Ecs::remove_disabled_systems`](Ecs::remove_disabled_systems).
+    #[serde(skip)]
+    pub disabled_systems: Vec<SystemName>,
 }
 
 impl Ecs {
     pub(crate) fn finish(&mut self) -> Result<(), EcsError> {
         self.assign_ids()?;
+        self.single_threaded |= cfg!(feature = "single-threaded");
+        self.apply_rust_types();
+        self.apply_alignments();
 
         let cloned_archetypes = self.archetypes.clone();
         for archetype in &mut self.archetypes {
@@ -48,6 +84,7 @@ impl Ecs {
         for system in &mut self.systems {
             system.finish(&self.archetypes);
         }
+        resolve_set_ordering(&mut self.systems, &self.set_ordering);
 
         for component in &mut self.components {
             component.finish(&self.archetypes, &self.systems);
@@ -63,6 +100,11 @@ impl Ecs {
 
         for phase in &mut self.phases {
             phase.finish();
+            phase.ignore_fixed_systems = phase.fixed
+                && self
+                    .systems
+                    .iter()
+                    .any(|system| system.ignore_fixed && system.phase == phase.name);
             self.any_phase_fixed |= phase.fixed;
             self.any_phase_on_request |= phase.on_request;
         }
@@ -74,9 +116,33 @@ impl Ecs {
                 &self.states,
                 &self.phases,
                 &self.views,
+                &mut self.diagnostics,
             )?;
         }
 
+        for world in &self.worlds {
+            for queue in &world.command_queues {
+                if !self.command_queues.contains(&queue.name) {
+                    self.command_queues.push(queue.name.clone());
+                }
+            }
+        }
+
+        let transfers: Vec<Vec<_>> = (0..self.worlds.len())
+            .map(|index| {
+                let world = &self.worlds[index];
+                self.worlds
+                    .iter()
+                    .enumerate()
+                    .filter(|&(other, _)| other != index)
+                    .filter_map(|(_, other)| world.transfer_to(other))
+                    .collect()
+            })
+            .collect();
+        for (world, transfers) in self.worlds.iter_mut().zip(transfers) {
+            world.transfers = transfers;
+        }
+
         Ok(())
     }
 
@@ -111,6 +177,47 @@ impl Ecs {
     }
 }
 
+fn default_max_component_size() -> usize {
+    256
+}
+
+/// Formats the "did you mean" hint of an error message for an optional suggestion.
+fn did_you_mean(suggestion: &Option<String>) -> String {
+    suggestion
+        .as_ref()
+        .map(|name| format!(" Did you mean '{name}'?"))
+        .unwrap_or_default()
+}
+
+/// Returns the candidate closest to `name` by case-insensitive edit distance, if any is
+/// close enough to plausibly be a typo.
+fn closest_name<'a>(name: &str, candidates: impl IntoIterator<Item = &'a str>) -> Option<&'a str> {
+    let name = name.to_lowercase();
+    let max_distance = (name.chars().count() / 3).max(1);
+    candidates
+        .into_iter()
+        .map(|candidate| (edit_distance(&name, &candidate.to_lowercase()), candidate))
+        .filter(|(distance, _)| *distance <= max_distance)
+        .min_by_key(|(distance, _)| *distance)
+        .map(|(_, candidate)| candidate)
+}
+
+/// Computes the Levenshtein distance between two strings.
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    let mut current = vec![0; b.len() + 1];
+    for (i, ca) in a.chars().enumerate() {
+        current[0] = i + 1;
+        for (j, cb) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(ca != *cb);
+            current[j + 1] = substitution.min(previous[j + 1] + 1).min(current[j] + 1);
+        }
+        std::mem::swap(&mut previous, &mut current);
+    }
+    previous[b.len()]
+}
+
 fn check_u32_capacity(kind: &'static str, count: usize) -> Result<(), EcsError> {
     if count > u32::MAX as usize {
         return Err(EcsError::TooManyIds { kind, count });
@@ -122,36 +229,103 @@ fn check_u32_capacity(kind: &'static str, count: usize) -> Result<(), EcsError>
 pub enum EcsError {
     #[error("Component '{0}' is defined more than once.")]
     DuplicateComponentDefinition(String),
-    #[error("Component '{0}' in archetype '{1}' is not defined in the ECS components.")]
-    MissingComponentInArchetype(String, String),
+    #[error("Component '{0}' declares an empty rust_type.")]
+    EmptyRustType(String),
+    #[error("Components '{1}' and '{2}' both use rust_type '{0}'.")]
+    DuplicateRustType(String, String, String),
+    #[error("Component '{0}' declares the alignment {1}, which is not a power of two.")]
+    InvalidComponentAlignment(String, usize),
+    #[error("Component '{0}' declares fields, which cannot be reflected for its rust_type.")]
+    FieldsWithRustType(String),
+    #[error("Component '{1}' declares the field '{0}' more than once.")]
+    DuplicateComponentField(String, String),
+    #[error("Component '{0}' in archetype '{1}' is not defined in the ECS components.{hint}", hint = did_you_mean(.2))]
+    MissingComponentInArchetype(String, String, Option<String>),
+    #[error("Component '{1}' requires component '{0}', which is not defined in the ECS components.{hint}", hint = did_you_mean(.2))]
+    MissingRequiredComponent(String, String, Option<String>),
+    #[error(
+        "Archetype '{0}' contains component '{1}' but not its required companion component '{2}'."
+    )]
+    MissingRequiredCompanionComponent(String, String, String),
     #[error("Component '{0}' in archetype '{1}' is referenced more than once.")]
     DuplicateComponentInArchetype(String, String),
-    #[error("Component '{0}' in system '{1}' is not defined in the ECS components.")]
-    MissingComponentInSystem(String, String),
+    #[error("Component '{0}' in system '{1}' is not defined in the ECS components.{hint}", hint = did_you_mean(.2))]
+    MissingComponentInSystem(String, String, Option<String>),
     #[error("Component '{0}' in system '{1}' is referenced more than once.")]
     DuplicateComponentInSystem(String, String),
     #[error("Duplicate archetype '{0}' and '{1}'")]
     DuplicateArchetype(String, String),
     #[error("System '{0}' is defined more than once.")]
     DuplicateSystem(String),
+    #[error("Failed to read the ECS definition: {0}")]
+    ReadDefinition(#[from] std::io::Error),
+    #[error("Failed to parse the ECS definition: {0}")]
+    InvalidDefinition(#[from] serde_yaml::Error),
+    #[error("Failed to load the finished ECS: {0}")]
+    InvalidFinishedEcs(#[from] serde_json::Error),
     #[error("Failed to process template: {0}")]
     TemplateError(#[from] minijinja::Error),
     #[error("System {0} requires components not covered by any archetype.")]
     NoMatchingArchetypeForSystem(String),
+    #[error(
+        "System {0} declares maybe components, but no inputs, outputs or entities to iterate them alongside."
+    )]
+    MaybeWithoutIteratedComponents(String),
+    #[error("Archetype '{0}' in the archetype_order of system {1} is not processed by the system.")]
+    UnmatchedArchetypeInOrder(String, String),
+    #[error("Archetype '{0}' is listed more than once in the archetype_order of system {1}.")]
+    DuplicateArchetypeInOrder(String, String),
     #[error("Promotion of archetype '{0}' to itself is not allowed.")]
     PromotionToSelf(String),
+    #[error("Archetype '{1}' extends undefined archetype '{0}'.")]
+    MissingArchetypeExtension(String, String),
+    #[error("A cycle was detected in the archetype extensions: {}.", .0.join(" -> "))]
+    ArchetypeExtensionCycle(Vec<String>),
     #[error("System {1} uses undefined phase '{0}'.")]
     MissingPhase(String, String),
+    #[error("System {0} sets ignore_fixed, but its phase '{1}' is not a fixed-time phase.")]
+    IgnoreFixedOutsideFixedPhase(String, String),
     #[error("World {0} uses no archetypes.")]
     WorldWithoutArchetypes(String),
+    #[error("The ECS defines no archetypes; at least one archetype is required to store entities.")]
+    NoArchetypes,
     #[error("World {1} uses undefined archetype {0}.")]
     MissingArchetypeInWorld(String, String),
+    #[error(
+        "Command queue '{0}' is defined more than once in world {1}; the name 'Default' is reserved for the default queue."
+    )]
+    DuplicateCommandQueue(String, String),
     #[error("A cycle was detected in the system run order: {}.", .0.join(" -> "))]
     CycleDetectedBetweenSystems(Vec<String>),
+    #[error(
+        "System {1} reads the neighbors' {0} component while also outputting it; neighbor lookups must be read-only."
+    )]
+    NeighborLookupOfOutput(String, String),
+    #[error(
+        "System {1} both reads the neighbors' {0} component and mutably looks it up; use only one of them."
+    )]
+    NeighborLookupOfMutableLookup(String, String),
+    #[error("System set '{0}' is used in the set ordering but no system belongs to it.")]
+    UndefinedSystemSet(String),
+    #[error("System set '{0}' is ordered against itself.")]
+    SystemSetOrderedAgainstItself(String),
     #[error("A cycle was detected in the system run order (run_after edges).")]
     CycleDetectedInSystemRunOrder,
+    #[error(
+        "The run_after and set_ordering requirements contradict each other: {}. Remove one of the run_after or set_ordering entries.",
+        .0.join(" -> ")
+    )]
+    ForcedOrderingCycle(Vec<String>),
     #[error("System {1} depends on undefined system {0}.")]
     MissingSystemDependency(String, String),
+    #[error(
+        "System {1} depends on system {0}, which is disabled. Enable it or remove the run_after entry."
+    )]
+    DependencyOnDisabledSystem(String, String),
+    #[error("Phase {1} runs after undefined phase '{0}'.")]
+    MissingPhaseDependency(String, String),
+    #[error("A cycle was detected in the phase run order: {}.", .0.join(" -> "))]
+    PhaseOrderingCycle(Vec<String>),
     #[error(
         "System {system} (phase '{system_phase}') has a run_after dependency on system {dependency} in phase '{dependency_phase}'. Cross-phase run_after edges have no effect; inter-phase ordering is enforced by phase order itself. Remove the dependency or move both systems into the same phase."
     )]
@@ -169,6 +343,8 @@ pub enum EcsError {
     MissingStateInSystem(String, String),
     #[error("State '{0}' is defined multiple times.")]
     StateDefinedMultipleTimes(String),
+    #[error("System {1} lists state '{0}' multiple times with conflicting access.")]
+    ConflictingStateUse(String, String),
     #[error(
         "Too many {kind}: {count} declared, but generated `#[repr(u32)]` IDs only support up to {max}.",
         max = u32::MAX
@@ -176,8 +352,8 @@ pub enum EcsError {
     TooManyIds { kind: &'static str, count: usize },
     #[error("View '{0}' is defined more than once.")]
     DuplicateView(String),
-    #[error("Component '{0}' in view '{1}' is not defined in the ECS components.")]
-    MissingComponentInView(String, String),
+    #[error("Component '{0}' in view '{1}' is not defined in the ECS components.{hint}", hint = did_you_mean(.2))]
+    MissingComponentInView(String, String, Option<String>),
     #[error("Component '{0}' in view '{1}' is referenced more than once.")]
     DuplicateComponentInView(String, String),
     #[error("View '{0}' requires components not covered by any archetype.")]
@@ -187,6 +363,64 @@ pub enum EcsError {
 }
 
 impl Ecs {
+    /// Merges the components of extended archetypes into the archetypes extending them, so that
+    /// all later validation sees the full component lists. The extended archetype's components
+    /// come first; a component listed in both is reported as a duplicate by
+    /// [`ensure_component_consistency`](Self::ensure_component_consistency).
+    pub(crate) fn expand_archetype_extensions(&mut self) -> Result<(), EcsError> {
+        let mut expanded: HashMap<ArchetypeName, Vec<ComponentRef>> = HashMap::new();
+        for archetype in &self.archetypes {
+            let mut path = Vec::new();
+            self.expand_archetype(archetype, &mut path, &mut expanded)?;
+        }
+
+        for archetype in &mut self.archetypes {
+            if let Some(components) = expanded.remove(&archetype.name) {
+                archetype.components = components;
+            }
+        }
+        Ok(())
+    }
+
+    fn expand_archetype(
+        &self,
+        archetype: &Archetype,
+        path: &mut Vec<ArchetypeName>,
+        expanded: &mut HashMap<ArchetypeName, Vec<ComponentRef>>,
+    ) -> Result<Vec<ComponentRef>, EcsError> {
+        if let Some(components) = expanded.get(&archetype.name) {
+            return Ok(components.clone());
+        }
+
+        let Some(base_name) = &archetype.extends else {
+            return Ok(archetype.components.clone());
+        };
+
+        if let Some(start) = path.iter().position(|name| name == &archetype.name) {
+            let mut cycle: Vec<_> = path[start..]
+                .iter()
+                .map(|name| name.type_name_raw.clone())
+                .collect();
+            cycle.push(archetype.name.type_name_raw.clone());
+            return Err(EcsError::ArchetypeExtensionCycle(cycle));
+        }
+
+        let Some(base) = self.archetypes.iter().find(|a| &a.name == base_name) else {
+            return Err(EcsError::MissingArchetypeExtension(
+                base_name.type_name_raw.clone(),
+                archetype.name.type_name.clone(),
+            ));
+        };
+
+        path.push(archetype.name.clone());
+        let mut components = self.expand_archetype(base, path, expanded)?;
+        path.pop();
+
+        components.extend(archetype.components.iter().cloned());
+        expanded.insert(archetype.name.clone(), components.clone());
+        Ok(components)
+    }
+
     pub(crate) fn ensure_distinct_archetype_components(&self) -> Result<(), EcsError> {
         let mut archetype_component_sets: HashMap<String, String> = HashMap::new();
         for archetype in &self.archetypes {
@@ -213,6 +447,84 @@ impl Ecs {
         Ok(())
     }
 
+    /// Sorts the phases so that each runs after the phases in its `run_after` list, keeping the
+    /// declaration order otherwise.
+    pub(crate) fn ensure_phase_order(&mut self) -> Result<(), EcsError> {
+        let index_of: HashMap<_, _> = self
+            .phases
+            .iter()
+            .enumerate()
+            .map(|(index, phase)| (&phase.name, index))
+            .collect();
+
+        let mut predecessors = vec![Vec::new(); self.phases.len()];
+        for (index, phase) in self.phases.iter().enumerate() {
+            for dependency in &phase.run_after {
+                let Some(&dependency) = index_of.get(dependency) else {
+                    return Err(EcsError::MissingPhaseDependency(
+                        dependency.type_name_raw.clone(),
+                        phase.name.type_name_raw.clone(),
+                    ));
+                };
+                predecessors[index].push(dependency);
+            }
+        }
+
+        // Repeatedly pick the first declared phase whose predecessors have all been placed.
+        let mut placed = vec![false; self.phases.len()];
+        let mut order = Vec::with_capacity(self.phases.len());
+        while order.len() < self.phases.len() {
+            let next = (0..self.phases.len())
+                .find(|&index| !placed[index] && predecessors[index].iter().all(|&p| placed[p]));
+            let Some(next) = next else {
+                return Err(EcsError::PhaseOrderingCycle(
+                    self.phase_cycle(&predecessors, &placed),
+                ));
+            };
+            placed[next] = true;
+            order.push(next);
+        }
+
+        let mut phases: Vec<_> = std::mem::take(&mut self.phases)
+            .into_iter()
+            .map(Some)
+            .collect();
+        self.phases = order
+            .into_iter()
+            .map(|index| phases[index].take().expect("Each phase is placed once"))
+            .collect();
+        Ok(())
+    }
+
+    /// Finds a cycle among the phases that could not be placed, in run order.
+    fn phase_cycle(&self, predecessors: &[Vec<usize>], placed: &[bool]) -> Vec<String> {
+        // Every unplaced phase waits for another unplaced phase, so walking backwards through
+        // them must eventually revisit one.
+        let mut path = vec![
+            placed
+                .iter()
+                .position(|&placed| !placed)
+                .expect("A phase is unplaced"),
+        ];
+        loop {
+            let current = *path.last().expect("The path is not empty");
+            let previous = *predecessors[current]
+                .iter()
+                .find(|&&p| !placed[p])
+                .expect("An unplaced phase waits for another unplaced phase");
+            if let Some(start) = path.iter().position(|&index| index == previous) {
+                path.drain(..start);
+                path.push(previous);
+                path.reverse();
+                return path
+                    .into_iter()
+                    .map(|index| self.phases[index].name.type_name_raw.clone())
+                    .collect();
+            }
+            path.push(previous);
+        }
+    }
+
     /// Ensure that all states are valid.
     pub(crate) fn ensure_state_consistency(&self) -> Result<(), EcsError> {
         let mut set = HashSet::new();
@@ -226,15 +538,142 @@ impl Ecs {
         Ok(())
     }
 
+    /// Refers to all components declaring a `rust_type` through that type instead of a generated one.
+    fn apply_rust_types(&mut self) {
+        let rust_types: HashMap<String, String> = self
+            .components
+            .iter()
+            .filter_map(|component| {
+                let rust_type = component.rust_type.as_deref()?.trim();
+                Some((component.name.type_name_raw.clone(), rust_type.to_string()))
+            })
+            .collect();
+        if rust_types.is_empty() {
+            return;
+        }
+
+        self.for_each_component_name_mut(|name| {
+            if let Some(rust_type) = rust_types.get(&name.type_name_raw) {
+                name.use_rust_type(rust_type);
+            }
+        });
+    }
+
+    /// Refers to all components declaring an `align` as stored with that alignment.
+    fn apply_alignments(&mut self) {
+        let alignments: HashMap<String, usize> = self
+            .components
+            .iter()
+            .filter_map(|component| Some((component.name.type_name_raw.clone(), component.align?)))
+            .collect();
+        if alignments.is_empty() {
+            return;
+        }
+
+        self.for_each_component_name_mut(|name| {
+            if let Some(&align) = alignments.get(&name.type_name_raw) {
+                name.use_alignment(align);
+            }
+        });
+    }
+
+    /// Calls `apply` for the name of every component definition and every reference to a component.
+    fn for_each_component_name_mut(&mut self, mut apply: impl FnMut(&mut ComponentName)) {
+        for component in &mut self.components {
+            apply(&mut component.name);
+        }
+        for archetype in &mut self.archetypes {
+            archetype.components.iter_mut().for_each(&mut apply);
+        }
+        for system in &mut self.systems {
+            system.inputs.iter_mut().for_each(&mut apply);
+            for output in &mut system.outputs {
+                apply(&mut output.component);
+            }
+            system.lookup.iter_mut().for_each(&mut apply);
+            system.lookup_mut.iter_mut().for_each(&mut apply);
+            system.neighbors.iter_mut().for_each(&mut apply);
+            system.maybe.iter_mut().for_each(&mut apply);
+        }
+        for view in &mut self.views {
+            view.components.iter_mut().for_each(&mut apply);
+        }
+    }
+
+    /// Suggests the defined component closest to an unknown component reference.
+    fn suggest_component(&self, component_ref: &ComponentRef) -> Option<String> {
+        closest_name(
+            &component_ref.type_name_raw,
+            self.components
+                .iter()
+                .map(|component| component.name.type_name_raw.as_str()),
+        )
+        .map(str::to_string)
+    }
+
+    /// Removes the systems that are not [`enabled`](System::enabled), remembering their names to
+    /// report `run_after` dependencies on them.
+    pub(crate) fn remove_disabled_systems(&mut self) {
+        let (enabled, disabled) = core::mem::take(&mut self.systems)
+            .into_iter()
+            .partition(|system| system.enabled);
+        self.systems = enabled;
+        self.disabled_systems = disabled
+            .into_iter()
+            .map(|system: System| system.name)
+            .collect();
+    }
+
     /// Ensure that all components used by archetypes are defined in the components vector of the ECS.
     pub(crate) fn ensure_component_consistency(&self) -> Result<(), EcsError> {
         let mut defined_components = HashSet::new();
+        let mut rust_types = HashMap::new();
         for component in &self.components {
             if !defined_components.insert(&component.name) {
                 return Err(EcsError::DuplicateComponentDefinition(
                     component.name.type_name.clone(),
                 ));
             }
+
+            if let Some(rust_type) = &component.rust_type {
+                let rust_type = rust_type.trim();
+                if rust_type.is_empty() {
+                    return Err(EcsError::EmptyRustType(
+                        component.name.type_name_raw.clone(),
+                    ));
+                }
+                if let Some(other) = rust_types.insert(rust_type, &component.name) {
+                    return Err(EcsError::DuplicateRustType(
+                        rust_type.to_string(),
+                        other.type_name_raw.clone(),
+                        component.name.type_name_raw.clone(),
+                    ));
+                }
+                if !component.fields.is_empty() {
+                    return Err(EcsError::FieldsWithRustType(
+                        component.name.type_name_raw.clone(),
+                    ));
+                }
+            }
+
+            if let Some(align) = component.align {
+                if !align.is_power_of_two() {
+                    return Err(EcsError::InvalidComponentAlignment(
+                        component.name.type_name_raw.clone(),
+                        align,
+                    ));
+                }
+            }
+
+            let mut fields = HashSet::new();
+            for field in &component.fields {
+                if !fields.insert(&field.name) {
+                    return Err(EcsError::DuplicateComponentField(
+                        field.name.clone(),
+                        component.name.type_name_raw.clone(),
+                    ));
+                }
+            }
         }
 
         for archetype in &self.archetypes {
@@ -251,6 +690,37 @@ impl Ecs {
                     return Err(EcsError::MissingComponentInArchetype(
                         component_ref.type_name.clone(),
                         archetype.name.type_name.clone(),
+                        self.suggest_component(component_ref),
+                    ));
+                }
+            }
+        }
+
+        for component in &self.components {
+            for required in &component.requires {
+                if !defined_components.contains(required) {
+                    return Err(EcsError::MissingRequiredComponent(
+                        required.type_name.clone(),
+                        component.name.type_name.clone(),
+                        self.suggest_component(required),
+                    ));
+                }
+            }
+
+            for archetype in &self.archetypes {
+                if !archetype.components.contains(&component.name) {
+                    continue;
+                }
+
+                if let Some(missing) = component
+                    .requires
+                    .iter()
+                    .find(|required| !archetype.components.contains(required))
+                {
+                    return Err(EcsError::MissingRequiredCompanionComponent(
+                        archetype.name.type_name.clone(),
+                        component.name.type_name.clone(),
+                        missing.type_name.clone(),
                     ));
                 }
             }
@@ -272,12 +742,31 @@ impl Ecs {
                     return Err(EcsError::MissingComponentInSystem(
                         component_ref.type_name.clone(),
                         system.name.type_name.clone(),
+                        self.suggest_component(component_ref),
                     ));
                 }
             }
 
             // Validate system outputs
-            for component_ref in &system.outputs {
+            for component_ref in system.outputs.iter().map(|output| &output.component) {
+                if !system_components.insert(component_ref) {
+                    return Err(EcsError::DuplicateComponentInSystem(
+                        component_ref.type_name.clone(),
+                        system.name.type_name.clone(),
+                    ));
+                }
+
+                if !defined_components.contains(component_ref) {
+                    return Err(EcsError::MissingComponentInSystem(
+                        component_ref.type_name.clone(),
+                        system.name.type_name.clone(),
+                        self.suggest_component(component_ref),
+                    ));
+                }
+            }
+
+            // Validate maybe components; these are read like inputs, but only where present.
+            for component_ref in &system.maybe {
                 if !system_components.insert(component_ref) {
                     return Err(EcsError::DuplicateComponentInSystem(
                         component_ref.type_name.clone(),
@@ -289,6 +778,75 @@ impl Ecs {
                     return Err(EcsError::MissingComponentInSystem(
                         component_ref.type_name.clone(),
                         system.name.type_name.clone(),
+                        self.suggest_component(component_ref),
+                    ));
+                }
+            }
+
+            if !system.maybe.is_empty()
+                && !system.entities
+                && system.inputs.is_empty()
+                && system.outputs.is_empty()
+            {
+                return Err(EcsError::MaybeWithoutIteratedComponents(
+                    system.name.type_name.clone(),
+                ));
+            }
+
+            // Validate mutable lookups; these may name the system's own inputs or outputs
+            // since they only reach other archetypes.
+            let mut lookup_components = HashSet::new();
+            for component_ref in &system.lookup_mut {
+                if !lookup_components.insert(component_ref) {
+                    return Err(EcsError::DuplicateComponentInSystem(
+                        component_ref.type_name.clone(),
+                        system.name.type_name.clone(),
+                    ));
+                }
+
+                if !defined_components.contains(component_ref) {
+                    return Err(EcsError::MissingComponentInSystem(
+                        component_ref.type_name.clone(),
+                        system.name.type_name.clone(),
+                        self.suggest_component(component_ref),
+                    ));
+                }
+            }
+
+            // Validate neighbor lookups; these are read while the system's own slices are
+            // borrowed, so they must not alias any mutable access of the system.
+            let mut neighbor_components = HashSet::new();
+            for component_ref in &system.neighbors {
+                if !neighbor_components.insert(component_ref) {
+                    return Err(EcsError::DuplicateComponentInSystem(
+                        component_ref.type_name.clone(),
+                        system.name.type_name.clone(),
+                    ));
+                }
+
+                if !defined_components.contains(component_ref) {
+                    return Err(EcsError::MissingComponentInSystem(
+                        component_ref.type_name.clone(),
+                        system.name.type_name.clone(),
+                        self.suggest_component(component_ref),
+                    ));
+                }
+
+                if system
+                    .outputs
+                    .iter()
+                    .any(|output| &output.component == component_ref)
+                {
+                    return Err(EcsError::NeighborLookupOfOutput(
+                        component_ref.type_name_raw.clone(),
+                        system.name.type_name.clone(),
+                    ));
+                }
+
+                if lookup_components.contains(component_ref) {
+                    return Err(EcsError::NeighborLookupOfMutableLookup(
+                        component_ref.type_name_raw.clone(),
+                        system.name.type_name.clone(),
                     ));
                 }
             }
@@ -325,6 +883,7 @@ impl Ecs {
                     return Err(EcsError::MissingComponentInView(
                         component_ref.type_name.clone(),
                         view.name.type_name_raw.clone(),
+                        self.suggest_component(component_ref),
                     ));
                 }
             }
@@ -347,6 +906,9 @@ impl Ecs {
     }
 
     pub(crate) fn ensure_world_consistency(&mut self) -> Result<(), EcsError> {
+        if self.archetypes.is_empty() {
+            return Err(EcsError::NoArchetypes);
+        }
         for world in &mut self.worlds {
             if world.archetypes_refs.is_empty() {
                 return Err(EcsError::WorldWithoutArchetypes(
@@ -354,13 +916,22 @@ impl Ecs {
                 ));
             }
             for archetype in &world.archetypes_refs {
-                if !self.archetypes.iter().any(|a| a.name.eq(&archetype)) {
+                if !self.archetypes.iter().any(|a| a.name.eq(archetype)) {
                     return Err(EcsError::MissingArchetypeInWorld(
                         archetype.type_name_raw.clone(),
                         world.name.type_name_raw.clone(),
                     ));
                 }
             }
+            let mut queue_names = HashSet::from(["Default"]);
+            for queue in &world.command_queues {
+                if !queue_names.insert(queue.name.type_name_raw.as_str()) {
+                    return Err(EcsError::DuplicateCommandQueue(
+                        queue.name.type_name_raw.clone(),
+                        world.name.type_name_raw.clone(),
+                    ));
+                }
+            }
         }
         Ok(())
     }
@@ -376,16 +947,41 @@ impl Ecs {
             }
         }
 
+        let defined_sets: HashSet<_> = self
+            .systems
+            .iter()
+            .flat_map(|system| system.sets.iter())
+            .collect();
+        for (before, after) in &self.set_ordering {
+            if before == after {
+                return Err(EcsError::SystemSetOrderedAgainstItself(before.clone()));
+            }
+            for set in [before, after] {
+                if !defined_sets.contains(set) {
+                    return Err(EcsError::UndefinedSystemSet(set.clone()));
+                }
+            }
+        }
+
         let system_phases: HashMap<_, _> =
             self.systems.iter().map(|s| (&s.name, &s.phase)).collect();
 
         for system in &self.systems {
-            let required_components: HashSet<_> =
-                system.inputs.iter().chain(&system.outputs).collect();
+            let required_components: HashSet<_> = system
+                .inputs
+                .iter()
+                .chain(system.outputs.iter().map(|output| &output.component))
+                .collect();
 
             // Ensure all `run_after` dependencies exist in self.systems
             for dependency in &system.run_after {
                 let Some(dep_phase) = system_phases.get(dependency) else {
+                    if self.disabled_systems.contains(dependency) {
+                        return Err(EcsError::DependencyOnDisabledSystem(
+                            dependency.type_name_raw.clone(),
+                            system.name.type_name.clone(),
+                        ));
+                    }
                     return Err(EcsError::MissingSystemDependency(
                         dependency.type_name_raw.clone(),
                         system.name.type_name.clone(),
@@ -419,26 +1015,68 @@ impl Ecs {
                         system.name.type_name.clone(),
                     ));
                 }
+
+                let access = state.clone().with_defaults();
+                if system.states.iter().any(|other| {
+                    other.name.eq(&state.name) && other.clone().with_defaults() != access
+                }) {
+                    return Err(EcsError::ConflictingStateUse(
+                        state.name.type_name_raw.clone(),
+                        system.name.type_name.clone(),
+                    ));
+                }
             }
 
-            if !self.phases.iter().any(|phase| phase.name.eq(&system.phase)) {
+            let Some(phase) = self
+                .phases
+                .iter()
+                .find(|phase| phase.name.eq(&system.phase))
+            else {
                 return Err(EcsError::MissingPhase(
                     system.phase.type_name_raw.clone(),
                     system.name.type_name.clone(),
                 ));
+            };
+
+            if system.ignore_fixed && phase.fixed_input == FixedTiming::None {
+                return Err(EcsError::IgnoreFixedOutsideFixedPhase(
+                    system.name.type_name.clone(),
+                    system.phase.type_name_raw.clone(),
+                ));
             }
 
-            if !self.archetypes.iter().any(|archetype| {
+            let matches = |archetype: &Archetype| {
                 archetype
                     .components
                     .iter()
                     .collect::<HashSet<_>>()
                     .is_superset(&required_components)
-            }) {
+            };
+            if !self.archetypes.iter().any(matches) {
                 return Err(EcsError::NoMatchingArchetypeForSystem(
                     system.name.type_name.clone(),
                 ));
             }
+
+            let mut ordered_archetypes = HashSet::new();
+            for name in &system.archetype_order {
+                if !ordered_archetypes.insert(name) {
+                    return Err(EcsError::DuplicateArchetypeInOrder(
+                        name.type_name_raw.clone(),
+                        system.name.type_name.clone(),
+                    ));
+                }
+                if !self
+                    .archetypes
+                    .iter()
+                    .any(|archetype| &archetype.name == name && matches(archetype))
+                {
+                    return Err(EcsError::UnmatchedArchetypeInOrder(
+                        name.type_name_raw.clone(),
+                        system.name.type_name.clone(),
+                    ));
+                }
+            }
         }
         Ok(())
     }
@@ -1,16 +1,20 @@
 //! Build-time dependency of `sillyecs`, a silly little Archetype ECS system.
 
 mod archetype;
+mod cache;
 mod code;
 mod component;
 mod ecs;
+mod naming;
+mod schema;
 mod state;
 mod system;
 mod system_scheduler;
 mod view;
 mod world;
 
-pub use crate::code::EcsCode;
+pub use crate::cache::FinishedEcs;
+pub use crate::code::{CodeWriters, EcsCode};
 pub use crate::ecs::EcsError;
 use serde::Serialize;
 use std::fmt::{Display, Formatter};
@@ -59,12 +63,11 @@ where
 
     let field_name = field_name.as_ref();
 
-    if field_name.ends_with('y') {
-        if field_name.len() >= 2 {
-            let before_y = field_name.chars().nth_back(1).unwrap();
-            if !"aeiou".contains(before_y) {
-                return format!("{}ies", &field_name[..field_name.len() - 1]);
-            }
+    if let Some(stem) = field_name.strip_suffix('y') {
+        if let Some(before_y) = stem.chars().next_back()
+            && !"aeiou".contains(before_y)
+        {
+            return format!("{stem}ies");
         }
         return format!("{field_name}s");
     }
@@ -86,22 +89,67 @@ where
     format!("{field_name}s")
 }
 
-fn snake_case_filter(value: String) -> String {
-    pascal_to_snake(value.trim())
+/// Converts a PascalCase identifier to snake_case.
+///
+/// Acronyms are kept together (`HTTPServer` becomes `http_server`) by default. Passing
+/// `"aggressive"` as the argument splits every uppercase letter instead (`h_t_t_p_server`).
+fn snake_case_filter(value: String, mode: Option<String>) -> Result<String, minijinja::Error> {
+    match mode.as_deref() {
+        None | Some("grouped") => Ok(pascal_to_snake(value.trim())),
+        Some("aggressive") => Ok(pascal_to_snake_aggressive(value.trim())),
+        Some(other) => Err(minijinja::Error::new(
+            minijinja::ErrorKind::InvalidOperation,
+            format!("unknown snake_case mode '{other}', expected 'grouped' or 'aggressive'"),
+        )),
+    }
 }
 
 /// Renders a (possibly multi-line) user string as the continuation of a Rust doc
 /// comment. The template is expected to emit the first `/// ` prefix; every line
 /// produced by this filter after a newline is prefixed with `/// ` so embedded
 /// newlines in YAML descriptions don't leak unguarded text into the generated
-/// Rust output.
-pub(crate) fn doc_lines_filter(value: String) -> String {
-    let trimmed = value.trim_end_matches('\n');
-    trimmed.replace('\n', "\n/// ")
+/// Rust output. The optional `indent` is the number of spaces the template
+/// indents the doc comment by.
+pub(crate) fn doc_lines_filter(value: String, indent: Option<usize>) -> String {
+    let indent = " ".repeat(indent.unwrap_or(0));
+    let mut lines = value.trim_end().lines();
+    let mut doc = lines.next().unwrap_or_default().to_string();
+    for line in lines {
+        doc.push('\n');
+        doc.push_str(&indent);
+        doc.push_str("///");
+        if !line.is_empty() {
+            doc.push(' ');
+            doc.push_str(line);
+        }
+    }
+    doc
 }
 
+/// Converts a PascalCase identifier to snake_case, keeping acronyms together.
 fn pascal_to_snake(type_name: &str) -> String {
-    let field_name = type_name
+    let chars: Vec<char> = type_name.chars().collect();
+    let mut snake = String::with_capacity(type_name.len() + 4);
+    for (index, &c) in chars.iter().enumerate() {
+        if c.is_uppercase() && index > 0 {
+            let previous = chars[index - 1];
+            let next_is_lower = chars.get(index + 1).is_some_and(|next| next.is_lowercase());
+            if previous.is_lowercase()
+                || previous.is_ascii_digit()
+                || (previous.is_uppercase() && next_is_lower)
+            {
+                snake.push('_');
+            }
+        }
+        snake.push(c.to_ascii_lowercase());
+    }
+    snake
+}
+
+/// Converts a PascalCase identifier to snake_case, treating every uppercase letter as the
+/// start of a new word.
+fn pascal_to_snake_aggressive(type_name: &str) -> String {
+    type_name
         .chars()
         .flat_map(|c| {
             if c.is_uppercase() {
@@ -111,8 +159,7 @@ fn pascal_to_snake(type_name: &str) -> String {
             }
         })
         .skip_while(|&c| c == '_')
-        .collect::<String>();
-    field_name
+        .collect::<String>()
 }
 
 #[cfg(test)]
@@ -124,18 +171,54 @@ mod tests {
         let cases = vec![
             ("PascalCase", "pascal_case"),
             ("SnakeCase", "snake_case"),
-            ("HTTPServer", "h_t_t_p_server"),
+            ("HTTPServer", "http_server"),
             ("", ""),
             ("lowercase", "lowercase"),
+            ("UPPERCASE", "uppercase"),
+            ("Mixed123Case", "mixed123_case"),
+            ("LoadGLTFMesh", "load_gltf_mesh"),
+        ];
+
+        for (input, expected) in cases {
+            assert_eq!(pascal_to_snake(input), expected);
+        }
+    }
+
+    #[test]
+    fn test_pascal_to_snake_aggressive() {
+        let cases = vec![
+            ("PascalCase", "pascal_case"),
+            ("HTTPServer", "h_t_t_p_server"),
             ("UPPERCASE", "u_p_p_e_r_c_a_s_e"),
             ("Mixed123Case", "mixed123_case"),
         ];
 
         for (input, expected) in cases {
-            assert_eq!(pascal_to_snake(&input.to_string()), expected);
+            assert_eq!(pascal_to_snake_aggressive(input), expected);
         }
     }
 
+    #[test]
+    fn test_snake_case_filter_modes() {
+        let mut env = minijinja::Environment::new();
+        env.add_filter("snake_case", snake_case_filter);
+        let render = |template: &str| env.render_str(template, ());
+
+        assert_eq!(
+            render("{{ 'HTTPServer' | snake_case }}").unwrap(),
+            "http_server"
+        );
+        assert_eq!(
+            render("{{ 'HTTPServer' | snake_case('grouped') }}").unwrap(),
+            "http_server"
+        );
+        assert_eq!(
+            render("{{ 'HTTPServer' | snake_case('aggressive') }}").unwrap(),
+            "h_t_t_p_server"
+        );
+        assert!(render("{{ 'HTTPServer' | snake_case('camel') }}").is_err());
+    }
+
     #[test]
     fn test_pluralize_name() {
         assert_eq!(pluralize_name("velocity"), "velocities");
@@ -148,4 +231,22 @@ mod tests {
         assert_eq!(pluralize_name("door"), "doors");
         assert_eq!(pluralize_name("stars"), "stars");
     }
+
+    #[test]
+    fn test_name_hash_is_consistent_with_eq() {
+        use std::collections::HashSet;
+
+        // Both share the type name `PositionComponent` but differ in their raw name.
+        let short = Name::new("Position".to_string(), "Component");
+        let long = Name::new("PositionComponent".to_string(), "Component");
+        assert_eq!(short.type_name, long.type_name);
+        assert_ne!(short.type_name_raw, long.type_name_raw);
+
+        // Equality is structural, so the names are distinct keys.
+        assert_ne!(short, long);
+        let set: HashSet<_> = [short.clone(), long.clone(), short.clone()].into();
+        assert_eq!(set.len(), 2);
+        assert!(set.contains(&short));
+        assert!(set.contains(&long));
+    }
 }
@@ -0,0 +1,108 @@
+//! Configurable suffixes of the generated type names.
+
+use serde::{Deserialize, Serialize};
+use std::cell::RefCell;
+
+/// The suffixes appended to the names of the ECS definitions to form their type names, e.g.
+/// `Position` becomes `PositionComponent`. Names already ending in their suffix are kept as they
+/// are, and a suffix may be empty to use the names unchanged.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Naming {
+    /// The suffix of component types. Defaults to `Component`.
+    pub component_suffix: String,
+    /// The suffix of archetype types. Defaults to `Archetype`.
+    pub archetype_suffix: String,
+    /// The suffix of system types. Defaults to `System`.
+    pub system_suffix: String,
+    /// The suffix of phase types. Defaults to `Phase`.
+    pub phase_suffix: String,
+    /// The suffix of world types. Defaults to `World`.
+    pub world_suffix: String,
+    /// The suffix of state types. Defaults to `State`.
+    pub state_suffix: String,
+    /// The suffix of view types. Defaults to `View`.
+    pub view_suffix: String,
+    /// The suffix of the command types of named command queues. Defaults to `Queue`.
+    pub queue_suffix: String,
+}
+
+impl Default for Naming {
+    fn default() -> Self {
+        Self {
+            component_suffix: "Component".to_string(),
+            archetype_suffix: "Archetype".to_string(),
+            system_suffix: "System".to_string(),
+            phase_suffix: "Phase".to_string(),
+            world_suffix: "World".to_string(),
+            state_suffix: "State".to_string(),
+            view_suffix: "View".to_string(),
+            queue_suffix: "Queue".to_string(),
+        }
+    }
+}
+
+thread_local! {
+    /// The naming used by names deserialized on this thread.
+    static NAMING: RefCell<Naming> = RefCell::new(Naming::default());
+}
+
+impl Naming {
+    /// Reads only the `naming` section of an ECS source, using the defaults if it is missing.
+    pub(crate) fn from_source(source: &[u8]) -> Result<Self, serde_yaml::Error> {
+        #[derive(Deserialize)]
+        struct Source {
+            #[serde(default)]
+            naming: Naming,
+        }
+
+        Ok(serde_yaml::from_slice::<Source>(source)?.naming)
+    }
+
+    /// Runs `f` with this naming applied to all names deserialized on the current thread.
+    pub(crate) fn apply<R>(self, f: impl FnOnce() -> R) -> R {
+        /// Restores the previous naming, even if `f` panics.
+        struct Restore(Option<Naming>);
+
+        impl Drop for Restore {
+            fn drop(&mut self) {
+                if let Some(previous) = self.0.take() {
+                    NAMING.set(previous);
+                }
+            }
+        }
+
+        let _restore = Restore(Some(NAMING.replace(self)));
+        f()
+    }
+
+    /// Returns the suffix selected from the naming in effect on the current thread.
+    pub(crate) fn suffix(select: fn(&Naming) -> &String) -> String {
+        NAMING.with_borrow(|naming| select(naming).clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Name;
+
+    #[test]
+    fn apply_restores_the_previous_naming() {
+        let naming = Naming {
+            component_suffix: String::new(),
+            ..Naming::default()
+        };
+        let name = naming.apply(|| {
+            Name::new(
+                "Position".to_string(),
+                &Naming::suffix(|naming| &naming.component_suffix),
+            )
+        });
+        assert_eq!(name.type_name, "Position");
+        assert_eq!(
+            Naming::suffix(|naming| &naming.component_suffix),
+            "Component"
+        );
+    }
+}
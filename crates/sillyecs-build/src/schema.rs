@@ -0,0 +1,246 @@
+//! A stable JSON description of a finished ECS for external tools, such as schedule visualizers
+//! and documentation generators.
+//!
+//! Names are given in their raw (unsuffixed) form and every list keeps the declaration order of
+//! the ECS, except for scheduled batches, which are listed in execution order.
+
+use crate::archetype::ArchetypeId;
+use crate::component::ComponentId;
+use crate::ecs::Ecs;
+use crate::system::SystemId;
+use crate::system_scheduler::{Access, Resource};
+use crate::world::WorldId;
+use serde::Serialize;
+
+#[derive(Serialize)]
+struct Schema<'a> {
+    components: Vec<ComponentSchema<'a>>,
+    archetypes: Vec<ArchetypeSchema<'a>>,
+    phases: Vec<PhaseSchema<'a>>,
+    systems: Vec<SystemSchema<'a>>,
+    states: Vec<StateSchema<'a>>,
+    worlds: Vec<WorldSchema<'a>>,
+}
+
+#[derive(Serialize)]
+struct ComponentSchema<'a> {
+    id: ComponentId,
+    name: &'a str,
+    version: u32,
+    affected_archetype_ids: &'a [ArchetypeId],
+    affected_system_ids: &'a [SystemId],
+}
+
+#[derive(Serialize)]
+struct ArchetypeSchema<'a> {
+    id: ArchetypeId,
+    name: &'a str,
+    components: Vec<&'a str>,
+    component_ids: &'a [ComponentId],
+    promotions: Vec<&'a str>,
+}
+
+#[derive(Serialize)]
+struct PhaseSchema<'a> {
+    name: &'a str,
+    manual: bool,
+    on_request: bool,
+    /// The rate of a fixed-time phase in Hertz, or `None` for variable-time phases.
+    fixed_hertz: Option<f32>,
+}
+
+#[derive(Serialize)]
+struct SystemSchema<'a> {
+    id: SystemId,
+    name: &'a str,
+    phase: &'a str,
+    inputs: Vec<&'a str>,
+    outputs: Vec<&'a str>,
+    run_after: Vec<&'a str>,
+    sets: &'a [String],
+    affected_archetype_ids: &'a [ArchetypeId],
+    dependencies: Vec<DependencySchema<'a>>,
+}
+
+#[derive(Serialize)]
+struct DependencySchema<'a> {
+    /// The kind of resource: `component`, `frame_context` or `state`.
+    resource: &'static str,
+    /// The name of the component or state, if any.
+    name: Option<&'a str>,
+    access: Access,
+}
+
+#[derive(Serialize)]
+struct StateSchema<'a> {
+    name: &'a str,
+    /// The systems only reading the state.
+    readers: Vec<&'a str>,
+    /// The systems writing the state in any of their hooks.
+    writers: Vec<&'a str>,
+}
+
+#[derive(Serialize)]
+struct WorldSchema<'a> {
+    id: WorldId,
+    name: &'a str,
+    archetypes: Vec<&'a str>,
+    phases: Vec<PhaseBatchesSchema<'a>>,
+}
+
+#[derive(Serialize)]
+struct PhaseBatchesSchema<'a> {
+    phase: &'a str,
+    /// The groups of systems in execution order; the systems of a group may run in parallel.
+    batches: Vec<Vec<&'a str>>,
+}
+
+/// Renders the schema of a finished ECS as pretty-printed JSON.
+pub(crate) fn schema_json(ecs: &Ecs) -> String {
+    let schema = Schema {
+        components: ecs
+            .components
+            .iter()
+            .map(|component| ComponentSchema {
+                id: component.id,
+                name: &component.name.type_name_raw,
+                version: component.version,
+                affected_archetype_ids: &component.affected_archetype_ids,
+                affected_system_ids: &component.affected_system_ids,
+            })
+            .collect(),
+        archetypes: ecs
+            .archetypes
+            .iter()
+            .map(|archetype| ArchetypeSchema {
+                id: archetype.id,
+                name: &archetype.name.type_name_raw,
+                components: archetype
+                    .components
+                    .iter()
+                    .map(|component| component.type_name_raw.as_str())
+                    .collect(),
+                component_ids: &archetype.component_ids,
+                promotions: archetype
+                    .promotion_infos
+                    .iter()
+                    .map(|promotion| promotion.target.type_name_raw.as_str())
+                    .collect(),
+            })
+            .collect(),
+        phases: ecs
+            .phases
+            .iter()
+            .map(|phase| PhaseSchema {
+                name: &phase.name.type_name_raw,
+                manual: phase.manual,
+                on_request: phase.on_request,
+                fixed_hertz: phase.fixed.then_some(phase.fixed_hertz),
+            })
+            .collect(),
+        systems: ecs
+            .systems
+            .iter()
+            .map(|system| {
+                let mut run_after: Vec<_> = system
+                    .run_after
+                    .iter()
+                    .map(|name| name.type_name_raw.as_str())
+                    .collect();
+                run_after.sort_unstable();
+                SystemSchema {
+                    id: system.id,
+                    name: &system.name.type_name_raw,
+                    phase: &system.phase.type_name_raw,
+                    inputs: system
+                        .inputs
+                        .iter()
+                        .map(|input| input.type_name_raw.as_str())
+                        .collect(),
+                    outputs: system
+                        .outputs
+                        .iter()
+                        .map(|output| output.type_name_raw.as_str())
+                        .collect(),
+                    run_after,
+                    sets: &system.sets,
+                    affected_archetype_ids: &system.affected_archetype_ids,
+                    dependencies: system
+                        .dependencies
+                        .iter()
+                        .map(|dependency| {
+                            let (resource, name) = match &dependency.resource {
+                                Resource::Component(name) => {
+                                    ("component", Some(name.type_name_raw.as_str()))
+                                }
+                                Resource::FrameContext => ("frame_context", None),
+                                Resource::UserState(name) => {
+                                    ("state", Some(name.type_name_raw.as_str()))
+                                }
+                            };
+                            DependencySchema {
+                                resource,
+                                name,
+                                access: dependency.access,
+                            }
+                        })
+                        .collect(),
+                }
+            })
+            .collect(),
+        states: ecs
+            .states
+            .iter()
+            .map(|state| StateSchema {
+                name: &state.name.type_name_raw,
+                readers: state
+                    .readers
+                    .iter()
+                    .map(|system| system.type_name_raw.as_str())
+                    .collect(),
+                writers: state
+                    .writers
+                    .iter()
+                    .map(|system| system.type_name_raw.as_str())
+                    .collect(),
+            })
+            .collect(),
+        worlds: ecs
+            .worlds
+            .iter()
+            .map(|world| WorldSchema {
+                id: world.id,
+                name: &world.name.type_name_raw,
+                archetypes: world
+                    .archetypes
+                    .iter()
+                    .map(|archetype| archetype.name.type_name_raw.as_str())
+                    .collect(),
+                phases: ecs
+                    .phases
+                    .iter()
+                    .map(|phase| PhaseBatchesSchema {
+                        phase: &phase.name.type_name_raw,
+                        batches: world
+                            .scheduled_systems
+                            .get(&phase.name)
+                            .map(|groups| {
+                                groups
+                                    .iter()
+                                    .map(|group| {
+                                        group
+                                            .iter()
+                                            .map(|system| system.name.type_name_raw.as_str())
+                                            .collect()
+                                    })
+                                    .collect()
+                            })
+                            .unwrap_or_default(),
+                    })
+                    .collect(),
+            })
+            .collect(),
+    };
+
+    serde_json::to_string_pretty(&schema).expect("The schema only uses string map keys")
+}
@@ -1,4 +1,5 @@
 use crate::Name;
+use crate::naming::Naming;
 use crate::system::{System, SystemNameRef};
 use serde::{Deserialize, Deserializer, Serialize};
 use std::hash::Hash;
@@ -11,13 +12,25 @@ pub struct State {
     pub description: Option<String>,
     #[serde(skip_deserializing)]
     pub systems: Vec<SystemNameRef>,
+    /// The systems only reading the state.
+    #[serde(skip_deserializing)]
+    pub readers: Vec<SystemNameRef>,
+    /// The systems writing the state in any of their hooks.
+    #[serde(skip_deserializing)]
+    pub writers: Vec<SystemNameRef>,
 }
 
 impl State {
     pub(crate) fn finish(&mut self, systems: &[System]) {
         for system in systems {
-            if system.states.iter().any(|s| s.name.eq(&self.name)) {
-                self.systems.push(system.name.clone());
+            let Some(state) = system.states.iter().find(|s| s.name.eq(&self.name)) else {
+                continue;
+            };
+            self.systems.push(system.name.clone());
+            if state.any_write() {
+                self.writers.push(system.name.clone());
+            } else {
+                self.readers.push(system.name.clone());
             }
         }
     }
@@ -43,6 +56,9 @@ impl<'de> Deserialize<'de> for StateName {
         D: Deserializer<'de>,
     {
         let type_name = String::deserialize(deserializer)?;
-        Ok(Self(Name::new(type_name, "State")))
+        Ok(Self(Name::new(
+            type_name,
+            &Naming::suffix(|naming| &naming.state_suffix),
+        )))
     }
 }
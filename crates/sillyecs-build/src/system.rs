@@ -1,6 +1,7 @@
 use crate::Name;
 use crate::archetype::{Archetype, ArchetypeId, ArchetypeRef};
 use crate::component::{ComponentName, ComponentRef};
+use crate::naming::Naming;
 use crate::state::StateName;
 use crate::system_scheduler::{Access, Dependency, Resource};
 use serde::{Deserialize, Deserializer, Serialize};
@@ -31,6 +32,14 @@ pub struct System {
     /// If no conflict is detected, calls may be parallelized.
     #[serde(default)]
     pub run_after: HashSet<SystemNameRef>,
+    /// The named system sets this system belongs to, ordered against each other by the
+    /// top-level `set_ordering`.
+    #[serde(default)]
+    pub sets: Vec<String>,
+    /// The systems of the same phase that must run before this system because of the
+    /// `set_ordering` of its sets. Filled in while finishing the ECS.
+    #[serde(skip)]
+    pub set_predecessors: HashSet<SystemNameRef>,
     /// Whether the system requires access to entities.
     #[serde(
         default,
@@ -52,21 +61,49 @@ pub struct System {
     /// Whether the system requires access to components of other entities, and which ones.
     #[serde(default)]
     pub lookup: Vec<ComponentRef>,
+    /// Components of other entities the system may mutate while it runs. Only archetypes the
+    /// system does not iterate itself are reachable through this lookup.
+    #[serde(default)]
+    pub lookup_mut: Vec<ComponentRef>,
+    /// Components of other entities the system reads while it runs, including the components it
+    /// iterates itself, e.g. the positions of neighbors. The system must not output them.
+    #[serde(default)]
+    pub neighbors: Vec<ComponentRef>,
     /// Whether the system uses a preflight phase.
     #[serde(default)]
     pub preflight: bool,
     /// Whether the system uses a postflight phase.
     #[serde(default)]
     pub postflight: bool,
+    /// Whether the system is generated and scheduled. A disabled system is treated as if it
+    /// were not declared at all, e.g. to temporarily turn it off during development.
+    #[serde(default = "enabled_by_default", skip_serializing)]
+    pub enabled: bool,
     /// The phase in which to run the system.
     pub phase: SystemPhaseRef,
+    /// Run this system once per frame with the variable frame delta, even though its phase
+    /// uses a fixed timing loop. Only valid for systems in fixed phases.
+    #[serde(default)]
+    pub ignore_fixed: bool,
+    /// Run the system's body, preflight and postflight even when none of its archetypes has
+    /// any entities. By default, these are skipped in that case.
+    #[serde(default)]
+    pub always_run: bool,
     /// The optional input components to the system.
     #[serde(default)]
     pub inputs: Vec<ComponentName>,
     /// The optional output components to the system.
     #[serde(default)]
-    pub outputs: Vec<ComponentName>,
-    /// The archetypes this system operates on. Available after a call to [`System::finish`](System::finish).
+    pub outputs: Vec<SystemOutput>,
+    /// Components the system reads where present. Unlike inputs, these do not restrict the
+    /// archetypes the system operates on; entities of archetypes lacking them receive `None`.
+    #[serde(default)]
+    pub maybe: Vec<ComponentName>,
+    /// The order in which the system visits its archetypes, e.g. to draw opaque archetypes
+    /// before transparent ones. Archetypes not listed are visited afterwards, ordered by name.
+    #[serde(default)]
+    pub archetype_order: Vec<ArchetypeRef>,
+    /// The archetypes this system operates on, in the order it visits them. Available after a call to [`System::finish`](System::finish).
     #[serde(skip_deserializing, default)]
     pub affected_archetypes: Vec<ArchetypeRef>,
     /// The IDs of the affected archetypes in ascending order. Available after a call to [`System::finish`](System::finish).
@@ -75,9 +112,23 @@ pub struct System {
     /// The number of affected archetypes. Available after a call to [`System::finish`](System::finish).
     #[serde(skip_deserializing, default)]
     pub affected_archetype_count: usize,
+    /// The archetypes reachable through [`System::lookup_mut`](System::lookup_mut), per component. Available after a call to [`System::finish`](System::finish).
+    #[serde(skip_deserializing, default)]
+    pub lookup_mut_targets: Vec<LookupTarget>,
+    /// The archetypes reachable through [`System::neighbors`](System::neighbors), per component. Available after a call to [`System::finish`](System::finish).
+    #[serde(skip_deserializing, default)]
+    pub neighbor_targets: Vec<LookupTarget>,
+    /// Whether each affected archetype holds each [`System::maybe`](System::maybe) component. Available after a call to [`System::finish`](System::finish).
+    #[serde(skip_deserializing, default)]
+    pub maybe_targets: Vec<MaybeTarget>,
     /// The code to iterate component values. Available after a call to [`System::finish`](System::finish).
     #[serde(skip_deserializing, default)]
     pub component_iter_code: String,
+    /// The code to iterate the component slices of all affected archetypes. Equals
+    /// [`System::component_iter_code`](System::component_iter_code) unless the system has
+    /// [`System::maybe`](System::maybe) components. Available after a call to [`System::finish`](System::finish).
+    #[serde(skip_deserializing, default)]
+    pub archetype_iter_code: String,
     /// The code to untuple component values. Available after a call to [`System::finish`](System::finish).
     #[serde(skip_deserializing, default)]
     pub component_untuple_code: String,
@@ -86,14 +137,87 @@ pub struct System {
     pub dependencies: Vec<Dependency>,
 }
 
+#[derive(Debug, Clone, Serialize)]
+pub struct LookupTarget {
+    /// The component to look up.
+    pub component: ComponentRef,
+    /// The archetypes holding the component that are reachable through the lookup, in ascending ID order.
+    pub archetypes: Vec<ArchetypeRef>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct MaybeTarget {
+    /// The component read where present.
+    pub component: ComponentRef,
+    /// Whether each affected archetype holds the component, in the order of [`System::affected_archetypes`](System::affected_archetypes).
+    pub present: Vec<bool>,
+}
+
+/// An output component of a system.
+///
+/// Deserialized either from a plain component name, or from a map such as
+/// `{ component: Force, accumulate: true }`.
+#[derive(Debug, Clone, Eq, PartialEq, Hash, Serialize)]
+pub struct SystemOutput {
+    /// The component written by the system.
+    #[serde(flatten)]
+    pub component: ComponentName,
+    /// Whether the system only accumulates into the component in a commutative way (e.g. summing
+    /// forces), so that it may share a parallel group with other accumulating systems.
+    pub accumulate: bool,
+}
+
+impl Deref for SystemOutput {
+    type Target = ComponentName;
+
+    fn deref(&self) -> &Self::Target {
+        &self.component
+    }
+}
+
+impl<'de> Deserialize<'de> for SystemOutput {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        #[derive(Deserialize)]
+        #[serde(untagged)]
+        enum Output {
+            Name(ComponentName),
+            Detailed {
+                component: ComponentName,
+                #[serde(default)]
+                accumulate: bool,
+            },
+        }
+
+        Ok(match Output::deserialize(deserializer)? {
+            Output::Name(component) => Self {
+                component,
+                accumulate: false,
+            },
+            Output::Detailed {
+                component,
+                accumulate,
+            } => Self {
+                component,
+                accumulate,
+            },
+        })
+    }
+}
+
 #[derive(Debug, Clone, Eq, PartialEq, Ord, PartialOrd, Serialize, Deserialize)]
 pub struct StateUse {
-    /// The name of the state.
-    #[serde(rename = "use")]
+    /// The name of the state. Deserialized from `use`, or from `state` for older schemas.
+    #[serde(rename = "use", alias = "state")]
     pub name: StateName,
     /// How the default access level.
     #[serde(default)]
     pub default: AccessType,
+    /// Shorthand for `default: write`, kept for schemas predating per-hook access levels.
+    #[serde(default, skip_serializing)]
+    pub write: bool,
     /// How the readiness check accesses the state.
     #[serde(default)]
     pub check: Option<AccessType>,
@@ -121,18 +245,31 @@ impl AccessType {
 }
 
 impl StateUse {
+    /// The access level of every hook that does not specify its own, honoring the legacy
+    /// [`write`](Self::write) shorthand.
+    pub const fn default_access(&self) -> AccessType {
+        if self.write {
+            AccessType::Write
+        } else {
+            self.default
+        }
+    }
+
     pub fn any_write(&self) -> bool {
-        self.check.unwrap_or(self.default).is_write()
-            || self.begin_phase.unwrap_or(self.default).is_write()
-            || self.preflight.unwrap_or(self.default).is_write()
-            || self.system.unwrap_or(self.default).is_write()
-            || self.postflight.unwrap_or(self.default).is_write()
-            || self.end_phase.unwrap_or(self.default).is_write()
+        let default = self.default_access();
+        self.check.unwrap_or(default).is_write()
+            || self.begin_phase.unwrap_or(default).is_write()
+            || self.preflight.unwrap_or(default).is_write()
+            || self.system.unwrap_or(default).is_write()
+            || self.postflight.unwrap_or(default).is_write()
+            || self.end_phase.unwrap_or(default).is_write()
     }
 
     /// Fills every unset lifecycle access hook with [`Self::default`] so templates can rely on
     /// concrete `none`/`read`/`write` values instead of falling through on null.
     pub fn apply_defaults(&mut self) {
+        self.default = self.default_access();
+        self.write = false;
         set_default_state(&mut self.check, self.default);
         set_default_state(&mut self.begin_phase, self.default);
         set_default_state(&mut self.preflight, self.default);
@@ -140,6 +277,13 @@ impl StateUse {
         set_default_state(&mut self.postflight, self.default);
         set_default_state(&mut self.end_phase, self.default);
     }
+
+    /// Returns this use with [`Self::apply_defaults`] applied, e.g. to compare the effective
+    /// access of two uses of the same state.
+    pub fn with_defaults(mut self) -> Self {
+        self.apply_defaults();
+        self
+    }
 }
 
 fn set_default_state(state: &mut Option<AccessType>, default: AccessType) {
@@ -148,7 +292,21 @@ fn set_default_state(state: &mut Option<AccessType>, default: AccessType) {
     }
 }
 
+fn enabled_by_default() -> bool {
+    true
+}
+
 impl System {
+    /// Indicates whether any hook of this system writes the state with the given name.
+    ///
+    /// The name may be given with or without the `State` suffix.
+    #[allow(dead_code)]
+    pub fn writes_state(&self, name: &str) -> bool {
+        self.states.iter().any(|state| {
+            (state.name.type_name_raw == name || state.name.type_name == name) && state.any_write()
+        })
+    }
+
     pub(crate) fn finish_dependencies(&mut self) {
         self.dependencies.clear();
 
@@ -162,10 +320,35 @@ impl System {
         // Add outputs as dependencies.
         self.dependencies
             .extend(self.outputs.iter().map(|output| Dependency {
-                resource: Resource::Component(output.clone()),
+                resource: Resource::Component(output.component.clone()),
+                access: if output.accumulate {
+                    Access::Accumulate
+                } else {
+                    Access::Write
+                },
+            }));
+
+        // Add mutable lookups as dependencies.
+        self.dependencies
+            .extend(self.lookup_mut.iter().map(|component| Dependency {
+                resource: Resource::Component(component.clone()),
                 access: Access::Write,
             }));
 
+        // Add maybe components as dependencies.
+        self.dependencies
+            .extend(self.maybe.iter().map(|component| Dependency {
+                resource: Resource::Component(component.clone()),
+                access: Access::Read,
+            }));
+
+        // Add neighbor lookups as dependencies.
+        self.dependencies
+            .extend(self.neighbors.iter().map(|component| Dependency {
+                resource: Resource::Component(component.clone()),
+                access: Access::Read,
+            }));
+
         // Add frame context and state to dependencies
         if self.context {
             self.dependencies.push(Dependency {
@@ -189,6 +372,9 @@ impl System {
         for state in &mut self.states {
             state.apply_defaults();
         }
+        // Uses listing the same state again were checked to grant the same access.
+        let mut seen = HashSet::new();
+        self.states.retain(|state| seen.insert(state.name.clone()));
     }
 
     pub(crate) fn finish(&mut self, archetypes: &[Archetype]) {
@@ -221,8 +407,78 @@ impl System {
         self.affected_archetype_ids = ids_and_names.iter().map(|entry| entry.0).collect();
         self.affected_archetypes = ids_and_names.into_iter().map(|entry| entry.1).collect();
 
+        // Visit the archetypes in the stable order of their names, moving the explicitly ordered
+        // archetypes to the front.
+        self.affected_archetypes
+            .sort_by(|a, b| a.type_name_raw.cmp(&b.type_name_raw));
+        for (index, name) in self.archetype_order.iter().enumerate() {
+            if let Some(position) = self.affected_archetypes.iter().position(|a| a == name) {
+                let archetype = self.affected_archetypes.remove(position);
+                self.affected_archetypes.insert(index, archetype);
+            }
+        }
+
+        // Mutable lookups may only reach archetypes the system does not iterate, so that they
+        // never alias the component slices handed to the system itself.
+        self.lookup_mut_targets = self
+            .lookup_mut
+            .iter()
+            .map(|component| {
+                let mut targets: Vec<_> = archetypes
+                    .iter()
+                    .filter(|archetype| !self.affected_archetype_ids.contains(&archetype.id))
+                    .filter(|archetype| archetype.components.contains(component))
+                    .map(|archetype| (archetype.id, archetype.name.clone()))
+                    .collect();
+                targets.sort_unstable_by_key(|entry| entry.0);
+                LookupTarget {
+                    component: component.clone(),
+                    archetypes: targets.into_iter().map(|entry| entry.1).collect(),
+                }
+            })
+            .collect();
+
+        // Neighbor lookups are read-only and the system outputs none of their components, so they
+        // may share every archetype with the component slices handed to the system.
+        self.neighbor_targets = self
+            .neighbors
+            .iter()
+            .map(|component| {
+                let mut targets: Vec<_> = archetypes
+                    .iter()
+                    .filter(|archetype| archetype.components.contains(component))
+                    .map(|archetype| (archetype.id, archetype.name.clone()))
+                    .collect();
+                targets.sort_unstable_by_key(|entry| entry.0);
+                LookupTarget {
+                    component: component.clone(),
+                    archetypes: targets.into_iter().map(|entry| entry.1).collect(),
+                }
+            })
+            .collect();
+
+        // Maybe components do not restrict the affected archetypes, so each archetype either
+        // hands out its slice or nothing.
+        self.maybe_targets = self
+            .maybe
+            .iter()
+            .map(|component| MaybeTarget {
+                component: component.clone(),
+                present: self
+                    .affected_archetypes
+                    .iter()
+                    .map(|name| {
+                        archetypes
+                            .iter()
+                            .find(|archetype| &archetype.name == name)
+                            .is_some_and(|archetype| archetype.components.contains(component))
+                    })
+                    .collect(),
+            })
+            .collect();
+
         // Create zipped iteration code.
-        let mut num_components = self.inputs.len() + self.outputs.len();
+        let mut num_components = self.inputs.len() + self.outputs.len() + self.maybe.len();
         if self.entities {
             num_components += 1;
         }
@@ -235,14 +491,15 @@ impl System {
                 self.component_iter_code = "entities".to_string();
                 self.component_untuple_code = "entity".to_string();
             } else if let Some(output) = self.outputs.first() {
-                self.component_iter_code = format!("{name}", name = output.field_name_plural);
-                self.component_untuple_code = format!("{name}", name = output.field_name);
+                self.component_iter_code = output.field_name_plural.clone();
+                self.component_untuple_code = output.field_name.clone();
             } else if let Some(input) = self.inputs.first() {
-                self.component_iter_code = format!("{name}", name = input.field_name_plural);
-                self.component_untuple_code = format!("{name}", name = input.field_name);
+                self.component_iter_code = input.field_name_plural.clone();
+                self.component_untuple_code = input.field_name.clone();
             } else {
                 unreachable!();
             }
+            self.archetype_iter_code = self.component_iter_code.clone();
         } else {
             // Multi-component case: emit a `.zip(...)`-chained iterator with a
             // trailing `.map(...)` that flattens the right-nested tuple into a
@@ -256,52 +513,101 @@ impl System {
             // The argument order (entity, inputs..., outputs...) must match
             // the order in which the surrounding templates feed the bindings
             // to `apply_single` / `apply_many`.
+            //
+            // The first two outputs are paired through `::sillyecs::ZipMut2`,
+            // a single vetted adapter for two simultaneous mutable borrows,
+            // instead of relying on the zip chain; the pair counts as one
+            // iterator with a `(a, b)` pattern.
+            //
+            // A maybe component is an `Option` of a slice per archetype. Per entity, it yields
+            // `Some` for each element of a present slice, or `None` for as long as the other
+            // iterators run; the iterators over archetypes just yield the options themselves.
             let mut iters: Vec<String> = Vec::with_capacity(num_components);
+            let mut archetype_iters: Vec<String> = Vec::with_capacity(num_components);
+            let mut patterns: Vec<String> = Vec::with_capacity(num_components);
             let mut names: Vec<String> = Vec::with_capacity(num_components);
 
             if self.entities {
                 iters.push("entities.iter()".to_string());
+                archetype_iters.push("entities.iter()".to_string());
+                patterns.push("entity".to_string());
                 names.push("entity".to_string());
             }
             for input in &self.inputs {
                 iters.push(format!("{name}.iter()", name = input.field_name_plural));
+                archetype_iters.push(format!("{name}.iter()", name = input.field_name_plural));
+                patterns.push(input.field_name.to_string());
                 names.push(input.field_name.to_string());
             }
-            for output in &self.outputs {
+            for component in &self.maybe {
+                iters.push(format!(
+                    "{name}.into_iter().flatten().map(Some).chain(::core::iter::repeat(None))",
+                    name = component.field_name_plural
+                ));
+                archetype_iters.push(format!(
+                    "{name}.into_iter()",
+                    name = component.field_name_plural
+                ));
+                patterns.push(component.field_name.to_string());
+                names.push(component.field_name.to_string());
+            }
+            let mut outputs = self.outputs.iter();
+            if self.outputs.len() >= 2 {
+                let (first, second) = (outputs.next().unwrap(), outputs.next().unwrap());
+                iters.push(format!(
+                    "::sillyecs::ZipMut2::new(&mut {a}[..], &mut {b}[..])",
+                    a = first.field_name_plural,
+                    b = second.field_name_plural
+                ));
+                archetype_iters.push(iters.last().unwrap().clone());
+                patterns.push(format!("({}, {})", first.field_name, second.field_name));
+                names.push(first.field_name.to_string());
+                names.push(second.field_name.to_string());
+            }
+            for output in outputs {
                 iters.push(format!(
                     "{name}.iter_mut()",
                     name = output.field_name_plural
                 ));
+                archetype_iters.push(iters.last().unwrap().clone());
+                patterns.push(output.field_name.to_string());
                 names.push(output.field_name.to_string());
             }
 
-            // Build the zip chain: `iters[0].zip(iters[1]).zip(iters[2])...`.
-            let mut iter_expr = iters[0].clone();
-            for next in &iters[1..] {
-                iter_expr = format!("{iter_expr}.zip({next})");
-            }
+            self.component_iter_code = zip_code(&iters, &patterns, &names);
+            self.archetype_iter_code = zip_code(&archetype_iters, &patterns, &names);
+            self.component_untuple_code = format!("({})", names.join(", "));
+        }
+    }
+}
 
-            // For N >= 3, the chained `.zip(...)` yields a right-nested tuple
-            // `((a, b), c)` etc. Add a `.map(...)` that destructures the
-            // nesting back into a flat tuple. For N == 2 the zip output is
-            // already a flat 2-tuple, so we skip the map to keep the emitted
-            // code minimal.
-            if iters.len() >= 3 {
-                // Closure input pattern: walk from the innermost zip outward
-                // so the pattern matches the right-nested zip output.
-                // Example for 4 iters: `(((a, b), c), d)`.
-                let mut closure_pat = format!("({}, {})", names[0], names[1]);
-                for name in &names[2..] {
-                    closure_pat = format!("({closure_pat}, {name})");
-                }
-                let flat_tuple = format!("({})", names.join(", "));
-                iter_expr = format!("{iter_expr}.map(|{closure_pat}| {flat_tuple})");
-            }
+/// Chains the iterators with `.zip(...)` and flattens the zipped tuples into `(names...)`.
+fn zip_code(iters: &[String], patterns: &[String], names: &[String]) -> String {
+    // Build the zip chain: `iters[0].zip(iters[1]).zip(iters[2])...`.
+    let mut iter_expr = iters[0].clone();
+    for next in &iters[1..] {
+        iter_expr = format!("{iter_expr}.zip({next})");
+    }
 
-            self.component_iter_code = iter_expr;
-            self.component_untuple_code = format!("({})", names.join(", "));
+    // For N >= 3, the chained `.zip(...)` yields a right-nested tuple
+    // `((a, b), c)` etc. Add a `.map(...)` that destructures the
+    // nesting back into a flat tuple. When the zip output already is
+    // a flat tuple (two plain iterators, or the output pair alone),
+    // we skip the map to keep the emitted code minimal.
+    let is_flat = patterns.len() == 1 || patterns == names;
+    if !(is_flat && iters.len() <= 2) {
+        // Closure input pattern: walk from the innermost zip outward
+        // so the pattern matches the right-nested zip output.
+        // Example for 4 iters: `(((a, b), c), d)`.
+        let mut closure_pat = format!("({}, {})", patterns[0], patterns[1]);
+        for pattern in &patterns[2..] {
+            closure_pat = format!("({closure_pat}, {pattern})");
         }
+        let flat_tuple = format!("({})", names.join(", "));
+        iter_expr = format!("{iter_expr}.map(|{closure_pat}| {flat_tuple})");
     }
+
+    iter_expr
 }
 
 #[derive(Debug, Default, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize)]
@@ -322,6 +628,10 @@ pub struct SystemPhase {
     /// Indicates that this phase is conditionally executed on a request.
     #[serde(default)]
     pub on_request: bool,
+    /// The phases that must run before this one. Phases not ordered this way run in the order
+    /// of their declaration.
+    #[serde(default, skip_serializing)]
+    pub run_after: Vec<SystemPhaseRef>,
     /// Whether the system requires access to the user state (and which ones).
     #[serde(default, rename(serialize = "states", deserialize = "states"))]
     pub states: Vec<StateUse>,
@@ -334,6 +644,9 @@ pub struct SystemPhase {
     /// Indicates whether this phase is fixed. Available after a call to [`SystemPhase::finish`](SystemPhase::finish).
     #[serde(default, skip_deserializing)]
     pub fixed: bool,
+    /// Indicates whether any system of this fixed phase opts out of the fixed timing loop. Available after a call to [`Ecs::finish`](crate::ecs::Ecs::finish).
+    #[serde(default, skip_deserializing)]
+    pub ignore_fixed_systems: bool,
 }
 
 #[derive(Debug, Copy, Clone, PartialEq, PartialOrd, Default)]
@@ -441,7 +754,10 @@ impl<'de> Deserialize<'de> for SystemPhaseName {
         D: Deserializer<'de>,
     {
         let type_name = String::deserialize(deserializer)?;
-        Ok(Self(Name::new(type_name, "Phase")))
+        Ok(Self(Name::new(
+            type_name,
+            &Naming::suffix(|naming| &naming.phase_suffix),
+        )))
     }
 }
 
@@ -471,6 +787,34 @@ impl<'de> Deserialize<'de> for SystemName {
         D: Deserializer<'de>,
     {
         let type_name = String::deserialize(deserializer)?;
-        Ok(Self(Name::new(type_name, "System")))
+        Ok(Self(Name::new(
+            type_name,
+            &Naming::suffix(|naming| &naming.system_suffix),
+        )))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn legacy_write_flag_marks_state_as_written() {
+        let system: System = serde_yaml::from_str(
+            r#"
+name: Input
+phase: Update
+states:
+  - use: Foo
+    write: true
+  - state: Bar
+"#,
+        )
+        .unwrap();
+
+        assert!(system.writes_state("Foo"));
+        assert!(system.writes_state("FooState"));
+        assert!(!system.writes_state("Bar"));
+        assert!(!system.writes_state("Baz"));
     }
 }
@@ -3,7 +3,8 @@
 //! This module provides functionality to analyze system dependencies and group them into
 //! parallelizable batches for efficient execution. It handles:
 //!
-//! - Component read/write dependencies between systems
+//! - Component read/write dependencies between systems, where commutative accumulating writes
+//!   of the same component do not conflict with each other
 //! - Explicit ordering requirements
 //! - Resource conflict resolution
 //! - Parallel batch scheduling
@@ -26,11 +27,12 @@
 //! cycle-break step below.
 //!
 //! The cycle-break step uses the same lexicographic comparison: the edge whose source system
-//! has the lexicographically *greatest* name is dropped. **Forced `run_after` edges are
-//! preferred-to-keep**: cycle-break only drops a forced edge when every edge in the cycle is
-//! forced (which means the user specified a contradiction). When a cycle is broken — and
-//! especially when a forced edge is dropped — sillyecs emits a `cargo:warning` so the user
-//! sees the scheduler had to override their intent.
+//! has the lexicographically *greatest* name is dropped. **Forced `run_after` edges are never
+//! dropped**: a cycle made up entirely of forced edges means the user specified contradictory
+//! requirements and is rejected up front with [`EcsError::ForcedOrderingCycle`]. Any other cycle
+//! contains at least one resource-induced edge, and one of those is dropped instead. When a cycle
+//! is broken, sillyecs records a diagnostic (see `EcsCode::diagnostics`) so the user sees the
+//! scheduler had to pick an order.
 //!
 //! After Kahn's algorithm produces a layer, the layer is also sorted by name, so the sequential
 //! call order *within* a parallel group is independent of YAML declaration order.
@@ -43,13 +45,45 @@
 use crate::component::ComponentName;
 use crate::ecs::EcsError;
 use crate::state::StateNameRef;
-use crate::system::{System, SystemId};
+use crate::system::{System, SystemId, SystemPhaseRef};
+use serde::Serialize;
 use std::collections::{HashMap, HashSet, VecDeque};
 
-#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+/// Reports a scheduling decision as a `tracing` event of the `sillyecs::schedule` target.
+///
+/// Without the `trace-schedule` feature the invocation expands to nothing, so the arguments are
+/// never evaluated.
+macro_rules! trace_schedule {
+    ($($arg:tt)*) => {
+        #[cfg(feature = "trace-schedule")]
+        tracing::debug!(target: "sillyecs::schedule", $($arg)*);
+    };
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash, Serialize)]
+#[serde(rename_all = "snake_case")]
 pub enum Access {
     Read,
     Write,
+    /// A commutative write (e.g. summing forces); only conflicts with reads and plain writes.
+    Accumulate,
+}
+
+impl Access {
+    /// Indicates whether this access mutates the resource.
+    const fn is_write(self) -> bool {
+        matches!(self, Access::Write | Access::Accumulate)
+    }
+}
+
+impl Dependency {
+    /// Indicates whether this dependency conflicts with a write by `other`, i.e. whether `other`
+    /// writes a resource this dependency accesses. Two accumulating accesses never conflict.
+    fn conflicts_with_write(&self, other: &Dependency) -> bool {
+        other.access.is_write()
+            && self.resource == other.resource
+            && !(self.access == Access::Accumulate && other.access == Access::Accumulate)
+    }
 }
 
 #[derive(Debug, Clone, Eq, PartialEq, Hash)]
@@ -68,6 +102,36 @@ pub enum Resource {
     UserState(StateNameRef),
 }
 
+#[cfg(feature = "trace-schedule")]
+impl core::fmt::Display for Resource {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Resource::Component(name) => write!(f, "component {}", name.type_name_raw),
+            Resource::FrameContext => f.write_str("frame context"),
+            Resource::UserState(name) => write!(f, "state {}", name.type_name_raw),
+        }
+    }
+}
+
+/// Lists the resources `writer` writes that `other` accesses in a conflicting way.
+#[cfg(feature = "trace-schedule")]
+fn conflicting_resources(writer: &System, other: &System) -> String {
+    let mut resources: Vec<String> = writer
+        .dependencies
+        .iter()
+        .filter(|dw| {
+            other
+                .dependencies
+                .iter()
+                .any(|d| d.conflicts_with_write(dw))
+        })
+        .map(|dw| dw.resource.to_string())
+        .collect();
+    resources.sort();
+    resources.dedup();
+    resources.join(", ")
+}
+
 /// Finds a cycle in `graph` and returns its edges in traversal order, or `None` if the graph is
 /// acyclic. Implemented as an iterative tri-color DFS over an explicit work stack so deep system
 /// graphs cannot overflow the thread stack.
@@ -149,19 +213,58 @@ fn cycle_path(
     path
 }
 
+/// Fills in the [`System::set_predecessors`] from the ordering between system sets: for every
+/// `(before, after)` pair, each system in the `after` set gets every system of the same phase in
+/// the `before` set as a predecessor. Systems in both sets are not ordered against themselves.
+///
+/// Set members of different phases are not ordered, as the phase order already applies to them.
+pub(crate) fn resolve_set_ordering(systems: &mut [System], set_ordering: &[(String, String)]) {
+    let members = |set: &String| -> Vec<(crate::system::SystemName, SystemPhaseRef)> {
+        systems
+            .iter()
+            .filter(|system| system.sets.contains(set))
+            .map(|system| (system.name.clone(), system.phase.clone()))
+            .collect()
+    };
+    let ordering: Vec<_> = set_ordering
+        .iter()
+        .map(|(before, after)| (members(before), after))
+        .collect();
+
+    for system in systems.iter_mut() {
+        system.set_predecessors.clear();
+        for (before, after) in &ordering {
+            if !system.sets.contains(after) {
+                continue;
+            }
+            system.set_predecessors.extend(
+                before
+                    .iter()
+                    .filter(|(name, phase)| *phase == system.phase && *name != system.name)
+                    .map(|(name, _)| name.clone()),
+            );
+        }
+    }
+}
+
 /// Schedules systems into parallelizable batches using resource dependencies and forced `run_after` ordering.
 ///
-/// Forced `run_after` edges are added first. Resource conflicts are then classified per-pair:
+/// Forced `run_after` and system set edges are added first. Resource conflicts are then classified per-pair:
 /// writer-vs-reader (one direction) and writer-vs-writer (bidirectional candidate). Writer-vs-reader
 /// edges are added directly, unless a forced chain already orders the pair in the opposite
 /// direction — the user's forced order wins. Bidirectional pairs are resolved in deterministic
 /// name order: a forced (transitively) reachable side wins; otherwise the name-based tie-break is
 /// applied *cycle-aware*, flipping direction if the name-earlier-predecessor choice would form a
 /// cycle with the already-committed graph. Any cycle that still remains is broken by removing the
-/// outgoing edge of the system whose name compares greatest, preferring to drop non-forced edges.
-/// Each cycle break emits a `cargo:warning` so the user is notified that their ordering
-/// constraints could not be fully satisfied. See the module-level docs for the rationale.
-pub fn schedule_systems(systems: &[System]) -> Result<Vec<Vec<SystemId>>, EcsError> {
+/// resource-induced outgoing edge of the system whose name compares greatest. Each cycle break
+/// adds a message to `diagnostics` so the user is notified that their data dependencies could not
+/// be fully satisfied. A cycle consisting entirely of forced `run_after` edges cannot be resolved
+/// without overriding the user and is returned as [`EcsError::ForcedOrderingCycle`]. See the
+/// module-level docs for the rationale.
+pub fn schedule_systems(
+    systems: &[System],
+    diagnostics: &mut Vec<String>,
+) -> Result<Vec<Vec<SystemId>>, EcsError> {
     let n = systems.len();
 
     // map names ↔ ids
@@ -180,12 +283,40 @@ pub fn schedule_systems(systems: &[System]) -> Result<Vec<Vec<SystemId>>, EcsErr
     for sys in systems {
         graph.entry(sys.id).or_default();
         for pred in &sys.run_after {
-            let p = id_by_name[&pred];
+            let p = id_by_name[pred];
+            graph.entry(p).or_default().insert(sys.id);
+            forced_edges.insert((p, sys.id));
+            trace_schedule!(
+                before = %pred.type_name_raw,
+                after = %sys.name.type_name_raw,
+                "added forced run_after edge"
+            );
+        }
+        // Set predecessors outside of the scheduled systems, e.g. in another world, are skipped.
+        for pred in &sys.set_predecessors {
+            let Some(&p) = id_by_name.get(pred) else {
+                continue;
+            };
             graph.entry(p).or_default().insert(sys.id);
             forced_edges.insert((p, sys.id));
+            trace_schedule!(
+                before = %pred.type_name_raw,
+                after = %sys.name.type_name_raw,
+                "added forced system set edge"
+            );
         }
     }
 
+    // A cycle among forced edges alone is a contradiction in the user's `run_after` requirements.
+    // Resource edges are only ever added on top, so checking the forced-only graph here catches
+    // every all-forced cycle.
+    if let Some(cycle_edges) = find_cycle(&graph) {
+        return Err(EcsError::ForcedOrderingCycle(cycle_path(
+            &cycle_edges,
+            &name_by_id,
+        )));
+    }
+
     // Build forced adjacency for reachability
     let mut forced_adj: HashMap<SystemId, Vec<SystemId>> = HashMap::new();
     for &(u, v) in &forced_edges {
@@ -251,24 +382,50 @@ pub fn schedule_systems(systems: &[System]) -> Result<Vec<Vec<SystemId>>, EcsErr
             if a.id >= b.id {
                 continue;
             }
-            let a_writes_shared = a.dependencies.iter().any(|da| {
-                da.access == Access::Write
-                    && b.dependencies.iter().any(|db| db.resource == da.resource)
-            });
-            let b_writes_shared = b.dependencies.iter().any(|db| {
-                db.access == Access::Write
-                    && a.dependencies.iter().any(|da| da.resource == db.resource)
-            });
+            let a_writes_shared = a
+                .dependencies
+                .iter()
+                .any(|da| b.dependencies.iter().any(|db| db.conflicts_with_write(da)));
+            let b_writes_shared = b
+                .dependencies
+                .iter()
+                .any(|db| a.dependencies.iter().any(|da| da.conflicts_with_write(db)));
             match (a_writes_shared, b_writes_shared) {
                 (false, false) => {}
                 (true, false) => {
                     if !forced_reachable(&forced_adj, b.id, a.id) {
                         graph.entry(a.id).or_default().insert(b.id);
+                        trace_schedule!(
+                            before = %a.name.type_name_raw,
+                            after = %b.name.type_name_raw,
+                            resources = %conflicting_resources(a, b),
+                            "added resource edge"
+                        );
+                    } else {
+                        trace_schedule!(
+                            before = %a.name.type_name_raw,
+                            after = %b.name.type_name_raw,
+                            resources = %conflicting_resources(a, b),
+                            "skipped resource edge contradicting the forced run_after order"
+                        );
                     }
                 }
                 (false, true) => {
                     if !forced_reachable(&forced_adj, a.id, b.id) {
                         graph.entry(b.id).or_default().insert(a.id);
+                        trace_schedule!(
+                            before = %b.name.type_name_raw,
+                            after = %a.name.type_name_raw,
+                            resources = %conflicting_resources(b, a),
+                            "added resource edge"
+                        );
+                    } else {
+                        trace_schedule!(
+                            before = %b.name.type_name_raw,
+                            after = %a.name.type_name_raw,
+                            resources = %conflicting_resources(b, a),
+                            "skipped resource edge contradicting the forced run_after order"
+                        );
                     }
                 }
                 (true, true) => {
@@ -283,11 +440,11 @@ pub fn schedule_systems(systems: &[System]) -> Result<Vec<Vec<SystemId>>, EcsErr
     // disagree with name ordering, so the sort key must be canonicalized to the name pair
     // `(min(name_a, name_b), max(name_a, name_b))` - otherwise YAML reordering could change
     // resolution order and, via the cycle-aware tie-break, the final schedule.
-    fn canonical_name_pair<'a>(
-        names: &'a HashMap<SystemId, crate::system::SystemName>,
+    fn canonical_name_pair(
+        names: &HashMap<SystemId, crate::system::SystemName>,
         a: SystemId,
         b: SystemId,
-    ) -> (&'a str, &'a str) {
+    ) -> (&str, &str) {
         let na = names[&a].type_name_raw.as_str();
         let nb = names[&b].type_name_raw.as_str();
         if na <= nb { (na, nb) } else { (nb, na) }
@@ -296,15 +453,33 @@ pub fn schedule_systems(systems: &[System]) -> Result<Vec<Vec<SystemId>>, EcsErr
         canonical_name_pair(&name_by_id, a1, b1).cmp(&canonical_name_pair(&name_by_id, a2, b2))
     });
 
+    #[cfg(feature = "trace-schedule")]
+    let system_by_id = systems
+        .iter()
+        .map(|sys| (sys.id, sys))
+        .collect::<HashMap<_, _>>();
+
     for (a_id, b_id) in bidirectional {
         let reach_ab = forced_reachable(&forced_adj, a_id, b_id);
         let reach_ba = forced_reachable(&forced_adj, b_id, a_id);
         if reach_ab && !reach_ba {
             graph.entry(a_id).or_default().insert(b_id);
+            trace_schedule!(
+                before = %name_by_id[&a_id].type_name_raw,
+                after = %name_by_id[&b_id].type_name_raw,
+                resources = %conflicting_resources(system_by_id[&a_id], system_by_id[&b_id]),
+                "resolved conflict by forced run_after order"
+            );
             continue;
         }
         if reach_ba && !reach_ab {
             graph.entry(b_id).or_default().insert(a_id);
+            trace_schedule!(
+                before = %name_by_id[&b_id].type_name_raw,
+                after = %name_by_id[&a_id].type_name_raw,
+                resources = %conflicting_resources(system_by_id[&b_id], system_by_id[&a_id]),
+                "resolved conflict by forced run_after order"
+            );
             continue;
         }
         // Cycle-aware name tie-break: prefer the alphabetically-earlier name as predecessor;
@@ -318,54 +493,63 @@ pub fn schedule_systems(systems: &[System]) -> Result<Vec<Vec<SystemId>>, EcsErr
         };
         if !would_cycle(&graph, pred, succ) {
             graph.entry(pred).or_default().insert(succ);
+            trace_schedule!(
+                before = %name_by_id[&pred].type_name_raw,
+                after = %name_by_id[&succ].type_name_raw,
+                resources = %conflicting_resources(system_by_id[&pred], system_by_id[&succ]),
+                "resolved conflict by name order"
+            );
         } else if !would_cycle(&graph, succ, pred) {
             graph.entry(succ).or_default().insert(pred);
+            trace_schedule!(
+                before = %name_by_id[&succ].type_name_raw,
+                after = %name_by_id[&pred].type_name_raw,
+                resources = %conflicting_resources(system_by_id[&succ], system_by_id[&pred]),
+                "resolved conflict against name order to avoid a cycle"
+            );
         } else {
             // Both directions would close a cycle: the conflict was unavoidable.
             // Keep the name direction and let the cycle-break step handle it.
             graph.entry(pred).or_default().insert(succ);
+            trace_schedule!(
+                before = %name_by_id[&pred].type_name_raw,
+                after = %name_by_id[&succ].type_name_raw,
+                resources = %conflicting_resources(system_by_id[&pred], system_by_id[&succ]),
+                "resolved conflict by name order; both directions close a cycle"
+            );
         }
     }
 
     // (Cycle detection lives at module scope; see `find_cycle`.)
 
-    // Remove one edge per cycle. Prefer dropping non-forced edges so user-specified `run_after`
-    // ordering is honored whenever possible; only drop a forced edge if every edge in the cycle
-    // is forced (the user has specified a contradictory ordering). Among the candidates, pick the
-    // edge whose source system has the lexicographically-greatest name so the choice is
-    // independent of YAML declaration order. Each cycle break is reported via `cargo:warning` so
-    // the user sees that their ordering or data-dependency constraints could not all be
-    // satisfied.
+    // Remove one edge per cycle. Only resource-induced edges are dropped so user-specified
+    // `run_after` ordering is always honored; all-forced cycles were rejected above, so every
+    // remaining cycle has at least one such edge. Among the candidates, pick the edge whose
+    // source system has the lexicographically-greatest name so the choice is independent of YAML
+    // declaration order. Each cycle break is reported as a diagnostic so the user sees that
+    // their data-dependency constraints could not all be satisfied.
     while let Some(cycle_edges) = find_cycle(&graph) {
-        let non_forced_pick = cycle_edges
+        let (rem_u, rem_v) = cycle_edges
             .iter()
             .filter(|e| !forced_edges.contains(e))
             .max_by_key(|&&(u, _)| &name_by_id[&u].type_name_raw)
-            .copied();
-        let (rem_u, rem_v) = match non_forced_pick {
-            Some(edge) => edge,
-            None => cycle_edges
-                .iter()
-                .max_by_key(|&&(u, _)| &name_by_id[&u].type_name_raw)
-                .copied()
-                .expect("find_cycle returned an empty cycle"),
-        };
+            .copied()
+            .expect("cycles consisting only of forced edges are rejected up front");
         let cycle_path: Vec<&str> = cycle_edges
             .iter()
             .map(|&(u, _)| name_by_id[&u].type_name_raw.as_str())
             .collect();
-        let dropped_forced = forced_edges.contains(&(rem_u, rem_v));
-        let detail = if dropped_forced {
-            " — dropping a USER-SPECIFIED run_after edge; the requested ordering cannot be satisfied"
-        } else {
-            ""
-        };
-        println!(
-            "cargo:warning=sillyecs scheduler: cycle detected through systems [{}], dropping edge {} -> {}{}",
+        diagnostics.push(format!(
+            "sillyecs scheduler: cycle detected through systems [{}], dropping edge {} -> {}",
             cycle_path.join(", "),
             name_by_id[&rem_u].type_name_raw,
             name_by_id[&rem_v].type_name_raw,
-            detail,
+        ));
+        trace_schedule!(
+            cycle = %cycle_path.join(" -> "),
+            before = %name_by_id[&rem_u].type_name_raw,
+            after = %name_by_id[&rem_v].type_name_raw,
+            "broke cycle by removing resource edge"
         );
         graph.get_mut(&rem_u).unwrap().remove(&rem_v);
     }
@@ -445,14 +629,17 @@ mod tests {
     use super::*;
     use crate::Name;
     use crate::component::ComponentName;
-    use crate::system::{System, SystemId, SystemName, SystemPhaseName, SystemPhaseRef};
+    use crate::system::{
+        System, SystemId, SystemName, SystemOutput, SystemPhaseName, SystemPhaseRef,
+    };
+    use proptest::prelude::*;
 
     fn sysname(name: &str) -> SystemName {
         SystemName(Name::new(name.to_string(), "System"))
     }
 
     fn compname(name: &str) -> ComponentName {
-        ComponentName(Name::new(name.to_string(), "Component"))
+        ComponentName::new(name.to_string())
     }
 
     fn phasename(name: &str) -> SystemPhaseRef {
@@ -470,19 +657,38 @@ mod tests {
             id: SystemId(id),
             name: sysname(name),
             run_after: prefer_after.into_iter().map(sysname).collect(),
+            sets: vec![],
+            set_predecessors: Default::default(),
             context: false,
             states: vec![],
             lookup: vec![],
+            lookup_mut: vec![],
+            neighbors: vec![],
             preflight: false,
             entities: false,
             commands: false,
             inputs: inputs.into_iter().map(compname).collect(),
-            outputs: outputs.into_iter().map(compname).collect(),
+            outputs: outputs
+                .into_iter()
+                .map(|name| SystemOutput {
+                    component: compname(name),
+                    accumulate: false,
+                })
+                .collect(),
+            maybe: vec![],
+            enabled: true,
             phase: phasename("default"),
+            ignore_fixed: false,
+            always_run: false,
+            archetype_order: vec![],
             affected_archetype_count: 0,
             affected_archetype_ids: Default::default(),
             affected_archetypes: Default::default(),
+            lookup_mut_targets: Default::default(),
+            neighbor_targets: Default::default(),
+            maybe_targets: Default::default(),
             component_iter_code: String::new(),
+            archetype_iter_code: String::new(),
             component_untuple_code: String::new(),
             description: None,
             dependencies: Default::default(),
@@ -492,6 +698,102 @@ mod tests {
         system
     }
 
+    fn create_accumulator(id: u64, name: &str, inputs: Vec<&str>, accumulates: &str) -> System {
+        let mut system = create_system(id, name, inputs, vec![accumulates], vec![]);
+        system.outputs[0].accumulate = true;
+        system.finish_dependencies();
+        system
+    }
+
+    /// Records the message and fields of every event as one line.
+    #[cfg(feature = "trace-schedule")]
+    #[derive(Default, Clone)]
+    struct EventRecorder(std::sync::Arc<std::sync::Mutex<Vec<String>>>);
+
+    #[cfg(feature = "trace-schedule")]
+    impl tracing::Subscriber for EventRecorder {
+        fn enabled(&self, _metadata: &tracing::Metadata<'_>) -> bool {
+            true
+        }
+
+        fn new_span(&self, _span: &tracing::span::Attributes<'_>) -> tracing::span::Id {
+            tracing::span::Id::from_u64(1)
+        }
+
+        fn record(&self, _span: &tracing::span::Id, _values: &tracing::span::Record<'_>) {}
+
+        fn record_follows_from(&self, _span: &tracing::span::Id, _follows: &tracing::span::Id) {}
+
+        fn event(&self, event: &tracing::Event<'_>) {
+            struct Line(String);
+
+            impl tracing::field::Visit for Line {
+                fn record_debug(
+                    &mut self,
+                    field: &tracing::field::Field,
+                    value: &dyn std::fmt::Debug,
+                ) {
+                    self.0.push_str(&format!(" {}={:?}", field.name(), value));
+                }
+            }
+
+            let mut line = Line(event.metadata().target().to_string());
+            event.record(&mut line);
+            self.0.lock().unwrap().push(line.0);
+        }
+
+        fn enter(&self, _span: &tracing::span::Id) {}
+
+        fn exit(&self, _span: &tracing::span::Id) {}
+    }
+
+    #[cfg(feature = "trace-schedule")]
+    #[test]
+    fn test_scheduling_decisions_are_traced() {
+        let systems = vec![
+            create_system(1, "A", vec![], vec!["Position"], vec![]),
+            create_system(2, "B", vec![], vec!["Position"], vec![]),
+            create_system(3, "C", vec![], vec![], vec!["A"]),
+        ];
+
+        let recorder = EventRecorder::default();
+        tracing::subscriber::with_default(recorder.clone(), || {
+            schedule_systems(&systems, &mut Vec::new()).unwrap();
+        });
+
+        let events = recorder.0.lock().unwrap();
+        assert!(events.iter().all(|e| e.starts_with("sillyecs::schedule ")));
+        assert!(
+            events
+                .iter()
+                .any(|e| e.contains("added forced run_after edge")
+                    && e.contains("before=A")
+                    && e.contains("after=C")),
+            "{events:?}"
+        );
+        assert!(
+            events
+                .iter()
+                .any(|e| e.contains("resolved conflict by name order")
+                    && e.contains("before=A")
+                    && e.contains("after=B")
+                    && e.contains("resources=component Position")),
+            "{events:?}"
+        );
+    }
+
+    fn ordered_names(systems: &[System]) -> Vec<(usize, &str)> {
+        let sorted = schedule_systems(systems, &mut Vec::new()).unwrap();
+        let mut ordered: Vec<(usize, &str)> = vec![];
+        for (group_idx, group) in sorted.iter().enumerate() {
+            for sys_id in group {
+                let sys = systems.iter().find(|s| s.id == *sys_id).unwrap();
+                ordered.push((group_idx, &sys.name.type_name_raw));
+            }
+        }
+        ordered
+    }
+
     #[test]
     fn no_preference_creates_three_groups() {
         // Systems are free to run in any order that creates the least amount of groups while
@@ -503,16 +805,14 @@ mod tests {
             create_system(4, "Backflow", vec!["y"], vec!["x"], vec![]), // creates a cycle
         ];
 
-        let sorted = schedule_systems(&systems).unwrap();
+        let sorted = schedule_systems(&systems, &mut Vec::new()).unwrap();
 
-        let mut counter = 0;
         let mut ordered: Vec<(usize, &str)> = vec![];
-        for group in sorted {
+        for (counter, group) in sorted.into_iter().enumerate() {
             for sys in group {
                 let sys = systems.iter().find(|s| s.id == sys).unwrap();
                 ordered.push((counter, &sys.name.type_name_raw));
             }
-            counter += 1;
         }
 
         assert_eq!(
@@ -538,16 +838,14 @@ mod tests {
             create_system(4, "Backflow", vec!["y"], vec!["x"], vec![]), // creates a cycle
         ];
 
-        let sorted = schedule_systems(&systems).unwrap();
+        let sorted = schedule_systems(&systems, &mut Vec::new()).unwrap();
 
-        let mut counter = 0;
         let mut ordered: Vec<(usize, &str)> = vec![];
-        for group in sorted {
+        for (counter, group) in sorted.into_iter().enumerate() {
             for sys in group {
                 let sys = systems.iter().find(|s| s.id == sys).unwrap();
                 ordered.push((counter, &sys.name.type_name_raw));
             }
-            counter += 1;
         }
 
         assert_eq!(
@@ -563,6 +861,86 @@ mod tests {
         );
     }
 
+    fn in_sets(mut system: System, sets: &[&str]) -> System {
+        system.sets = sets.iter().map(|set| set.to_string()).collect();
+        system
+    }
+
+    fn set_ordering(pairs: &[(&str, &str)]) -> Vec<(String, String)> {
+        pairs
+            .iter()
+            .map(|&(before, after)| (before.to_string(), after.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn set_ordering_runs_all_members_of_one_set_before_the_other() {
+        let mut systems = vec![
+            in_sets(
+                create_system(1, "Think", vec!["Target"], vec!["Intent"], vec![]),
+                &["ai"],
+            ),
+            in_sets(
+                create_system(2, "Steer", vec!["Intent"], vec![], vec![]),
+                &["ai"],
+            ),
+            in_sets(
+                create_system(3, "Gravity", vec![], vec!["Velocity"], vec![]),
+                &["physics"],
+            ),
+            in_sets(
+                create_system(4, "Integrate", vec!["Velocity"], vec!["Position"], vec![]),
+                &["physics"],
+            ),
+        ];
+        resolve_set_ordering(&mut systems, &set_ordering(&[("physics", "ai")]));
+
+        let ordered = ordered_names(&systems);
+        let layer_of = |name: &str| ordered.iter().find(|(_, n)| *n == name).unwrap().0;
+
+        for physics in ["Gravity", "Integrate"] {
+            for ai in ["Think", "Steer"] {
+                assert!(
+                    layer_of(physics) < layer_of(ai),
+                    "{physics} must run before {ai}: {ordered:?}"
+                );
+            }
+        }
+
+        // Within a set, the resource conflicts still order the systems.
+        assert!(layer_of("Gravity") < layer_of("Integrate"));
+        assert!(layer_of("Think") < layer_of("Steer"));
+    }
+
+    #[test]
+    fn set_ordering_only_applies_within_a_phase() {
+        let mut later = in_sets(
+            create_system(2, "Render", vec![], vec![], vec![]),
+            &["late"],
+        );
+        later.phase = phasename("render");
+        let mut systems = vec![
+            in_sets(
+                create_system(1, "Update", vec![], vec![], vec![]),
+                &["early"],
+            ),
+            later,
+            in_sets(
+                create_system(3, "Both", vec![], vec![], vec![]),
+                &["early", "late"],
+            ),
+        ];
+        resolve_set_ordering(&mut systems, &set_ordering(&[("early", "late")]));
+
+        assert!(systems[0].set_predecessors.is_empty());
+        assert!(systems[1].set_predecessors.is_empty());
+        assert_eq!(
+            systems[2].set_predecessors,
+            HashSet::from([sysname("Update")]),
+            "a member of both sets is not ordered against itself"
+        );
+    }
+
     /// Bidirectional resource conflict between two systems whose name order *disagrees* with
     /// `SystemId` order. The old ID-based tie-break would let the higher-`SystemId` system run
     /// first; the name-based tie-break makes the alphabetically-earlier name run first.
@@ -575,7 +953,7 @@ mod tests {
             create_system(2, "AlphaWriter", vec!["a"], vec!["b"], vec![]),
         ];
 
-        let sorted = schedule_systems(&systems).unwrap();
+        let sorted = schedule_systems(&systems, &mut Vec::new()).unwrap();
 
         let mut ordered: Vec<(usize, &str)> = vec![];
         for (group_idx, group) in sorted.iter().enumerate() {
@@ -609,7 +987,7 @@ mod tests {
             create_system(3, "Beta", vec!["b"], vec!["c"], vec![]),
         ];
 
-        let sorted = schedule_systems(&systems).unwrap();
+        let sorted = schedule_systems(&systems, &mut Vec::new()).unwrap();
 
         let mut ordered: Vec<(usize, &str)> = vec![];
         for (group_idx, group) in sorted.iter().enumerate() {
@@ -714,7 +1092,7 @@ mod tests {
             create_system(5, "FrameGlobals", vec![], vec!["g"], vec![]),
         ];
 
-        let sorted = schedule_systems(&systems).unwrap();
+        let sorted = schedule_systems(&systems, &mut Vec::new()).unwrap();
 
         let mut ordered: Vec<(usize, &str)> = vec![];
         for (group_idx, group) in sorted.iter().enumerate() {
@@ -759,4 +1137,200 @@ mod tests {
             );
         }
     }
+
+    #[test]
+    fn forced_only_cycle_is_an_error() {
+        let systems = vec![
+            create_system(1, "A", vec![], vec!["x"], vec!["B"]),
+            create_system(2, "B", vec![], vec!["y"], vec!["A"]),
+        ];
+
+        match schedule_systems(&systems, &mut Vec::new()) {
+            Err(EcsError::ForcedOrderingCycle(path)) => {
+                assert_eq!(path, vec!["A", "B", "A"]);
+            }
+            other => panic!("expected ForcedOrderingCycle, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn accumulators_of_same_component_share_a_group() {
+        let systems = vec![
+            create_accumulator(1, "Gravity", vec![], "force"),
+            create_accumulator(2, "Wind", vec!["position"], "force"),
+            create_accumulator(3, "Drag", vec![], "force"),
+        ];
+
+        assert_eq!(
+            ordered_names(&systems),
+            vec![(0, "Drag"), (0, "Gravity"), (0, "Wind")],
+        );
+    }
+
+    #[test]
+    fn accumulator_conflicts_with_readers_and_writers() {
+        let systems = vec![
+            create_accumulator(1, "Gravity", vec![], "force"),
+            create_accumulator(2, "Wind", vec![], "force"),
+            create_system(
+                3,
+                "Integrate",
+                vec!["force"],
+                vec!["velocity"],
+                vec!["Gravity", "Wind"],
+            ),
+            create_system(4, "Reset", vec![], vec!["force"], vec![]),
+        ];
+
+        let ordered = ordered_names(&systems);
+        let layer = |name: &str| ordered.iter().find(|(_, n)| *n == name).unwrap().0;
+        assert!(layer("Gravity") < layer("Integrate"));
+        assert!(layer("Wind") < layer("Integrate"));
+        for accumulator in ["Gravity", "Wind"] {
+            assert_ne!(
+                layer(accumulator),
+                layer("Reset"),
+                "{accumulator} must not share a layer with a plain writer of `force`"
+            );
+        }
+    }
+
+    /// The components the generated systems access.
+    const COMPONENTS: [&str; 4] = ["a", "b", "c", "d"];
+
+    /// The accesses of a generated system as bit masks over [`COMPONENTS`] and, for `run_after`,
+    /// over the systems declared before it, so that shrinking removes accesses and edges.
+    #[derive(Debug, Clone)]
+    struct SystemSpec {
+        inputs: u8,
+        outputs: u8,
+        accumulate: u8,
+        run_after: u16,
+    }
+
+    /// Generates up to 12 system specs, together with an order in which to assign names to them.
+    fn scheduling_input() -> impl Strategy<Value = (Vec<SystemSpec>, Vec<usize>)> {
+        let spec = (0..16u8, 0..16u8, 0..16u8, any::<u16>()).prop_map(
+            |(inputs, outputs, accumulate, run_after)| SystemSpec {
+                inputs,
+                outputs,
+                accumulate,
+                run_after,
+            },
+        );
+        proptest::collection::vec(spec, 1..=12).prop_flat_map(|specs| {
+            let name_order: Vec<usize> = (0..specs.len()).collect();
+            (Just(specs), Just(name_order).prop_shuffle())
+        })
+    }
+
+    /// Builds valid systems from the specs. The names are assigned in the given order, which is
+    /// independent of the IDs, to exercise the name-based tie-breaks.
+    fn build_systems(specs: &[SystemSpec], name_order: &[usize]) -> Vec<System> {
+        let names: Vec<String> = name_order.iter().map(|index| format!("S{index}")).collect();
+        let components = |mask: u8| {
+            COMPONENTS
+                .iter()
+                .enumerate()
+                .filter(move |(bit, _)| mask & (1 << bit) != 0)
+                .map(|(_, &component)| component)
+        };
+        specs
+            .iter()
+            .enumerate()
+            .map(|(index, spec)| {
+                // Only earlier systems can be preferred predecessors, so run_after is acyclic.
+                let run_after = names[..index]
+                    .iter()
+                    .enumerate()
+                    .filter(|(bit, _)| spec.run_after & (1 << bit) != 0)
+                    .map(|(_, name)| name.as_str())
+                    .collect();
+                // A component is either an input or an output of a system.
+                let mut system = create_system(
+                    index as u64 + 1,
+                    &names[index],
+                    components(spec.inputs & !spec.outputs).collect(),
+                    components(spec.outputs).collect(),
+                    run_after,
+                );
+                let accumulate: Vec<_> = components(spec.outputs & spec.accumulate).collect();
+                for output in &mut system.outputs {
+                    output.accumulate =
+                        accumulate.contains(&output.component.type_name_raw.as_str());
+                }
+                system.finish_dependencies();
+                system
+            })
+            .collect()
+    }
+
+    fn conflicting(a: &System, b: &System) -> bool {
+        a.dependencies.iter().any(|da| {
+            b.dependencies
+                .iter()
+                .any(|db| da.conflicts_with_write(db) || db.conflicts_with_write(da))
+        })
+    }
+
+    proptest! {
+        #![proptest_config(ProptestConfig {
+            cases: 512,
+            max_shrink_iters: 10_000,
+            ..ProptestConfig::default()
+        })]
+
+        #[test]
+        fn schedule_upholds_invariants((specs, name_order) in scheduling_input()) {
+            let systems = build_systems(&specs, &name_order);
+            let mut diagnostics = Vec::new();
+            let layers = schedule_systems(&systems, &mut diagnostics)
+                .expect("acyclic run_after requirements can always be scheduled");
+
+            // Every system appears exactly once.
+            let mut layer_of = HashMap::new();
+            for (index, layer) in layers.iter().enumerate() {
+                prop_assert!(!layer.is_empty(), "layer {} is empty", index);
+                for &id in layer {
+                    prop_assert!(layer_of.insert(id, index).is_none(), "{:?} is scheduled twice", id);
+                }
+            }
+            prop_assert_eq!(layer_of.len(), systems.len());
+
+            // Every run_after requirement is respected; forced edges are never dropped.
+            let id_by_name: HashMap<_, _> =
+                systems.iter().map(|system| (&system.name, system.id)).collect();
+            for system in &systems {
+                for predecessor in &system.run_after {
+                    prop_assert!(
+                        layer_of[&id_by_name[predecessor]] < layer_of[&system.id],
+                        "{} must run after {}",
+                        system.name.type_name_raw,
+                        predecessor.type_name_raw
+                    );
+                }
+            }
+
+            // Systems of one layer only share a resource written by either of them if the
+            // scheduler dropped the edge between them to break a cycle, and reported it.
+            for (index, a) in systems.iter().enumerate() {
+                for b in &systems[index + 1..] {
+                    if layer_of[&a.id] != layer_of[&b.id] || !conflicting(a, b) {
+                        continue;
+                    }
+                    let (a, b) = (&a.name.type_name_raw, &b.name.type_name_raw);
+                    prop_assert!(
+                        diagnostics.iter().any(|diagnostic| {
+                            diagnostic.ends_with(&format!("dropping edge {a} -> {b}"))
+                                || diagnostic.ends_with(&format!("dropping edge {b} -> {a}"))
+                        }),
+                        "conflicting systems {} and {} share a layer without a reported cycle break: {:?}",
+                        a,
+                        b,
+                        diagnostics
+                    );
+                }
+            }
+        }
+    }
 }
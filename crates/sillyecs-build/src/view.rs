@@ -1,6 +1,7 @@
 use crate::Name;
 use crate::archetype::{Archetype, ArchetypeId, ArchetypeRef};
 use crate::component::{Component, ComponentId, ComponentRef};
+use crate::naming::Naming;
 use serde::{Deserialize, Deserializer, Serialize};
 use std::collections::HashSet;
 use std::ops::Deref;
@@ -69,7 +70,12 @@ impl View {
                 components
                     .iter()
                     .find(|known| known.name.type_name == c.type_name)
-                    .expect("Component must exist; view consistency check should have caught this")
+                    .unwrap_or_else(|| {
+                        panic!(
+                            "Component `{}` of view `{}` is not defined; the view consistency check should have caught this",
+                            c.type_name_raw, self.name.type_name_raw
+                        )
+                    })
                     .id
             })
             .collect();
@@ -97,6 +103,9 @@ impl<'de> Deserialize<'de> for ViewName {
         D: Deserializer<'de>,
     {
         let type_name = String::deserialize(deserializer)?;
-        Ok(Self(Name::new(type_name, "View")))
+        Ok(Self(Name::new(
+            type_name,
+            &Naming::suffix(|naming| &naming.view_suffix),
+        )))
     }
 }
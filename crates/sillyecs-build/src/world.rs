@@ -2,6 +2,7 @@ use crate::Name;
 use crate::archetype::{Archetype, ArchetypeRef};
 use crate::component::ComponentRef;
 use crate::ecs::EcsError;
+use crate::naming::Naming;
 use crate::state::State;
 use crate::system::{System, SystemPhase, SystemPhaseRef};
 use crate::system_scheduler::schedule_systems;
@@ -19,6 +20,9 @@ pub struct World {
     pub description: Option<String>,
     #[serde(skip_serializing, rename(deserialize = "archetypes"))]
     pub archetypes_refs: Vec<ArchetypeRef>,
+    /// The named command queues of this world in addition to its default queue.
+    #[serde(default)]
+    pub command_queues: Vec<CommandQueue>,
     #[serde(skip_deserializing)]
     pub archetypes: Vec<Archetype>,
     #[serde(skip_deserializing)]
@@ -34,10 +38,59 @@ pub struct World {
     /// that codegen output is deterministic between runs.
     #[serde(default, skip_deserializing)]
     pub scheduled_systems: BTreeMap<SystemPhaseRef, Vec<Vec<System>>>,
+    /// The scheduled systems of each group, partitioned into lanes for parallel execution.
+    /// Systems accumulating into the same component share a lane and run one after another
+    /// on the same thread; all other systems get a lane of their own.
+    #[serde(default, skip_deserializing)]
+    pub parallel_lanes: BTreeMap<SystemPhaseRef, Vec<Vec<Vec<System>>>>,
     /// The components used in this world (based on this world's archetypes). Ordered by component
     /// and archetype name so that codegen output is deterministic between runs.
     #[serde(default, skip_deserializing)]
     pub components: BTreeMap<ComponentRef, BTreeSet<ArchetypeRef>>,
+    /// The other worlds that entities of this world can be transferred to, in declaration order.
+    /// Available after the ECS is finished, see [`World::transfer_to`](World::transfer_to).
+    #[serde(default, skip_deserializing)]
+    pub transfers: Vec<WorldTransfer>,
+}
+
+/// A named command queue of a world, drained at its own point of the frame.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CommandQueue {
+    pub name: CommandQueueName,
+    /// When the queued commands are applied.
+    #[serde(default)]
+    pub drain: CommandQueueDrain,
+}
+
+/// The point of the frame at which a [`CommandQueue`] is drained.
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CommandQueueDrain {
+    /// Before and after every phase, like the default command queue.
+    #[default]
+    Phase,
+    /// Once at the end of every frame.
+    Frame,
+}
+
+/// The archetypes of another world that the entities of a world can be transferred to.
+#[derive(Debug, Clone, Serialize)]
+pub struct WorldTransfer {
+    /// The world to transfer entities to.
+    pub target: WorldName,
+    /// The target archetype of each archetype that has one, in the order of the source world.
+    pub archetypes: Vec<ArchetypeTransfer>,
+}
+
+/// The archetype an entity ends up in when transferred to another world.
+#[derive(Debug, Clone, Serialize)]
+pub struct ArchetypeTransfer {
+    /// The archetype of the entity in the source world.
+    pub source: ArchetypeRef,
+    /// The archetype of the entity in the target world.
+    pub target: ArchetypeRef,
+    /// The components of the target archetype, all of which the source archetype contains.
+    pub components: Vec<ComponentRef>,
 }
 
 impl World {
@@ -48,6 +101,7 @@ impl World {
         states: &[State],
         phases: &[SystemPhase],
         views: &[View],
+        diagnostics: &mut Vec<String>,
     ) -> Result<(), EcsError> {
         let mut used_systems = HashSet::new();
         let mut used_states = HashSet::new();
@@ -81,7 +135,12 @@ impl World {
                             .iter()
                             .find(|s| s.name.eq(&state.name))
                             .cloned()
-                            .expect("Failed to find state that is known to exist");
+                            .unwrap_or_else(|| {
+                                panic!(
+                                    "State `{}` used by system `{}` is not defined",
+                                    state.name.type_name_raw, system.name.type_name_raw
+                                )
+                            });
 
                         assert!(
                             !self.states.iter().any(|s| s.name.eq(&state.name)),
@@ -94,7 +153,7 @@ impl World {
             }
         }
 
-        self.scheduled_systems(phases)?;
+        self.scheduled_systems(phases, diagnostics)?;
         if !self.systems.is_empty() {
             debug_assert_ne!(
                 self.scheduled_systems.len(),
@@ -132,8 +191,50 @@ impl World {
         Ok(())
     }
 
-    pub(crate) fn scheduled_systems(&mut self, phases: &[SystemPhase]) -> Result<(), EcsError> {
+    /// Determines where the entities of this world end up when transferred to `other`, or
+    /// [`None`] if no archetype of `other` can take any of them.
+    ///
+    /// The target of an archetype is the archetype of `other` with the most components that are
+    /// all contained in it, preferring the first declared one on ties.
+    pub(crate) fn transfer_to(&self, other: &World) -> Option<WorldTransfer> {
+        let mut archetypes = Vec::new();
+        for source in &self.archetypes {
+            let mut target: Option<&Archetype> = None;
+            for candidate in &other.archetypes {
+                let contained = candidate
+                    .components
+                    .iter()
+                    .all(|component| source.components.contains(component));
+                if contained
+                    && target
+                        .is_none_or(|target| candidate.components.len() > target.components.len())
+                {
+                    target = Some(candidate);
+                }
+            }
+
+            if let Some(target) = target {
+                archetypes.push(ArchetypeTransfer {
+                    source: source.name.clone(),
+                    target: target.name.clone(),
+                    components: target.components.clone(),
+                });
+            }
+        }
+
+        (!archetypes.is_empty()).then(|| WorldTransfer {
+            target: other.name.clone(),
+            archetypes,
+        })
+    }
+
+    pub(crate) fn scheduled_systems(
+        &mut self,
+        phases: &[SystemPhase],
+        diagnostics: &mut Vec<String>,
+    ) -> Result<(), EcsError> {
         let mut phase_groups = BTreeMap::new();
+        let mut phase_lanes = BTreeMap::new();
         for phase in phases {
             let systems_in_group: Vec<_> = self
                 .systems
@@ -141,7 +242,7 @@ impl World {
                 .filter(|s| s.phase == phase.name)
                 .cloned()
                 .collect();
-            let groups = schedule_systems(&systems_in_group)?;
+            let groups = schedule_systems(&systems_in_group, diagnostics)?;
             let scheduled_systems: Vec<_> = groups
                 .into_iter()
                 .map(|group| {
@@ -151,20 +252,67 @@ impl World {
                             self.systems
                                 .iter()
                                 .find(|s| s.id == system)
-                                .expect("Failed to find system")
+                                .unwrap_or_else(|| {
+                                    panic!(
+                                        "Scheduled system with ID {} of phase `{}` is not part of world `{}`",
+                                        system.0,
+                                        phase.name.type_name_raw,
+                                        self.name.type_name_raw
+                                    )
+                                })
                         })
                         .cloned()
                         .collect()
                 })
                 .collect();
+            let lanes = scheduled_systems
+                .iter()
+                .map(|group: &Vec<System>| parallel_lanes(group))
+                .collect();
+            phase_lanes.insert(phase.name.clone(), lanes);
             phase_groups.insert(phase.name.clone(), scheduled_systems);
         }
 
         self.scheduled_systems = phase_groups;
+        self.parallel_lanes = phase_lanes;
         Ok(())
     }
 }
 
+/// Partitions a scheduling group into lanes, chaining systems that accumulate into a shared
+/// component so that they never hold mutable access to the same data concurrently.
+fn parallel_lanes(group: &[System]) -> Vec<Vec<System>> {
+    let accumulates_into = |system: &System, component: &ComponentRef| {
+        system
+            .outputs
+            .iter()
+            .any(|output| output.accumulate && output.component.eq(component))
+    };
+
+    let mut lanes: Vec<Vec<System>> = Vec::new();
+    for system in group {
+        let mut lane = vec![system.clone()];
+        let mut index = 0;
+        while index < lanes.len() {
+            let shares_accumulator = lanes[index].iter().any(|other| {
+                system
+                    .outputs
+                    .iter()
+                    .any(|output| output.accumulate && accumulates_into(other, output))
+            });
+            if shares_accumulator {
+                let mut merged = lanes.remove(index);
+                merged.append(&mut lane);
+                lane = merged;
+            } else {
+                index += 1;
+            }
+        }
+        lanes.push(lane);
+    }
+    lanes
+}
+
 #[derive(Debug, Default, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize)]
 #[serde(transparent)]
 pub struct WorldId(pub(crate) u64);
@@ -187,6 +335,34 @@ impl<'de> Deserialize<'de> for WorldName {
         D: Deserializer<'de>,
     {
         let type_name = String::deserialize(deserializer)?;
-        Ok(Self(Name::new(type_name, "World")))
+        Ok(Self(Name::new(
+            type_name,
+            &Naming::suffix(|naming| &naming.world_suffix),
+        )))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize)]
+#[serde(transparent)]
+pub struct CommandQueueName(pub(crate) Name);
+
+impl Deref for CommandQueueName {
+    type Target = Name;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl<'de> Deserialize<'de> for CommandQueueName {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let type_name = String::deserialize(deserializer)?;
+        Ok(Self(Name::new(
+            type_name,
+            &Naming::suffix(|naming| &naming.queue_suffix),
+        )))
     }
 }
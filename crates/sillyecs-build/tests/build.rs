@@ -1,4 +1,4 @@
-use sillyecs_build::{EcsCode, EcsError};
+use sillyecs_build::{CodeWriters, EcsCode, EcsError, FinishedEcs};
 use std::io::BufReader;
 
 #[test]
@@ -149,6 +149,68 @@ systems:
     );
 }
 
+/// Phases are keyed by ordered maps, so the code emitted per phase must not depend on the
+/// iteration order of a randomly seeded hash map.
+#[test]
+fn multi_phase_output_is_byte_stable() {
+    const YAML: &str = r#"
+components:
+  - name: Position
+  - name: Velocity
+  - name: Health
+archetypes:
+  - name: Particle
+    components: [Position, Velocity]
+  - name: Creature
+    components: [Position, Velocity, Health]
+worlds:
+  - name: Main
+    archetypes: [Particle, Creature]
+phases:
+  - name: Input
+  - name: Physics
+    fixed: 60 Hz
+  - name: Update
+  - name: Render
+    manual: true
+  - name: Save
+    on_request: true
+systems:
+  - name: ReadInput
+    phase: Input
+    outputs: [Velocity]
+  - name: Integrate
+    phase: Physics
+    inputs: [Velocity]
+    outputs: [Position]
+  - name: Heal
+    phase: Update
+    outputs: [Health]
+  - name: Age
+    phase: Update
+    inputs: [Health]
+    run_after: [Heal]
+  - name: Draw
+    phase: Render
+    inputs: [Position]
+  - name: Persist
+    phase: Save
+    inputs: [Position, Health]
+"#;
+
+    let first =
+        EcsCode::generate(BufReader::new(YAML.as_bytes())).expect("generation must succeed");
+    for _ in 0..8 {
+        let again =
+            EcsCode::generate(BufReader::new(YAML.as_bytes())).expect("generation must succeed");
+        assert_eq!(first.components.as_bytes(), again.components.as_bytes());
+        assert_eq!(first.archetypes.as_bytes(), again.archetypes.as_bytes());
+        assert_eq!(first.systems.as_bytes(), again.systems.as_bytes());
+        assert_eq!(first.world.as_bytes(), again.world.as_bytes());
+        assert_eq!(first.to_schema_json(), again.to_schema_json());
+    }
+}
+
 /// Regression for issue #27: per-tick `Box::new(&self.archetypes)` heap allocation was emitted in
 /// preflight/postflight call sites of systems with `lookup:` entries. The trait method now takes
 /// `&dyn XComponentLookup` directly and the call sites pass `&self.archetypes` without boxing.
@@ -246,6 +308,41 @@ systems:
     }
 }
 
+/// `ignore_fixed` only has meaning inside a fixed-time phase; elsewhere it is a schema mistake.
+#[test]
+fn ignore_fixed_outside_fixed_phase_is_rejected() {
+    const YAML: &str = r#"
+components:
+  - name: Position
+archetypes:
+  - name: Particle
+    components: [Position]
+worlds:
+  - name: Main
+    archetypes: [Particle]
+phases:
+  - name: Update
+systems:
+  - name: Tick
+    phase: Update
+    ignore_fixed: true
+    outputs: [Position]
+"#;
+
+    let reader = BufReader::new(YAML.as_bytes());
+    let err = match EcsCode::generate(reader) {
+        Ok(_) => panic!("ignore_fixed in a variable phase must fail"),
+        Err(e) => e,
+    };
+    match err {
+        EcsError::IgnoreFixedOutsideFixedPhase(system, phase) => {
+            assert_eq!(system, "TickSystem");
+            assert_eq!(phase, "Update");
+        }
+        other => panic!("expected IgnoreFixedOutsideFixedPhase, got {other:?}"),
+    }
+}
+
 /// Issue #4: an archetype component view defines a fixed subset of components that may be
 /// shared across multiple archetypes. The world template must emit per-view struct and
 /// accessor pairs so that a single archetype match can return all requested components by
@@ -348,9 +445,10 @@ systems:
         Err(e) => e,
     };
     match err {
-        EcsError::MissingComponentInView(component, view) => {
+        EcsError::MissingComponentInView(component, view, suggestion) => {
             assert_eq!(component, "VelocityComponent");
             assert_eq!(view, "Bogus");
+            assert_eq!(suggestion, None);
         }
         other => panic!("expected MissingComponentInView, got {other:?}"),
     }
@@ -541,3 +639,1588 @@ systems:
         other => panic!("expected DuplicateSystem, got {other:?}"),
     }
 }
+
+/// The components template emits a `COMPONENTS` name/ID registry and a matching
+/// `component_id_by_name` lookup so data-driven tooling can resolve component names at runtime.
+/// The lookup itself is exercised in the `full_coverage` compile fixture.
+#[test]
+fn component_registry_is_generated() {
+    let file = include_str!("ecs.yaml");
+    let reader = BufReader::new(file.as_bytes());
+    let code = EcsCode::generate(reader).expect("Failed to build ECS");
+
+    assert!(
+        code.components
+            .contains("pub const COMPONENTS: &[(&str, u32)]")
+    );
+    assert!(code.components.contains("(\"Position\", "));
+    assert!(
+        code.components
+            .contains("pub fn component_id_by_name(name: &str) -> Option<ComponentId>")
+    );
+    assert!(
+        code.components
+            .contains("\"Position\" => Some(ComponentId::Position),")
+    );
+}
+
+/// Every world exposes its per-phase system membership through `systems_in_phase`, and the
+/// phase enum lists all phases in declaration order.
+#[test]
+fn systems_in_phase_is_generated() {
+    let file = include_str!("ecs.yaml");
+    let reader = BufReader::new(file.as_bytes());
+    let code = EcsCode::generate(reader).expect("Failed to build ECS");
+
+    assert!(
+        code.systems
+            .contains("pub const ALL: [SystemPhase; Self::COUNT]")
+    );
+    assert!(
+        code.systems
+            .contains("pub const fn name(&self) -> &'static str")
+    );
+    assert!(code.world.contains(
+        "pub const fn systems_in_phase(&self, phase: SystemPhase) -> &'static [SystemId]"
+    ));
+}
+
+/// A `lookup_mut` only borrows the component storage of archetypes the system does not iterate,
+/// so it can coexist with the system's own mutable component slices.
+#[test]
+fn lookup_mut_borrows_only_other_archetypes() {
+    const YAML: &str = r#"
+components:
+  - name: Position
+  - name: Velocity
+archetypes:
+  - name: Particle
+    components: [Position, Velocity]
+  - name: Anchor
+    components: [Position]
+worlds:
+  - name: Main
+    archetypes: [Particle, Anchor]
+phases:
+  - name: Update
+systems:
+  - name: Attract
+    phase: Update
+    inputs: [Velocity]
+    outputs: [Position]
+    lookup_mut: [Position]
+"#;
+    let code = EcsCode::generate(BufReader::new(YAML.as_bytes())).expect("Failed to build ECS");
+
+    assert!(
+        code.systems
+            .contains("pub struct AttractComponentLookupMut<'a>")
+    );
+    assert!(
+        code.systems
+            .contains("anchor_positions: &'a mut [PositionComponent],")
+    );
+    assert!(
+        !code.systems.contains("particle_positions"),
+        "lookup must not borrow the iterated archetype"
+    );
+    assert!(
+        code.systems
+            .contains("lookup_mut: &mut AttractComponentLookupMut<'_>,")
+    );
+    assert!(code.world.contains("&mut lookup_mut,"));
+}
+
+#[test]
+fn lookup_mut_rejects_undefined_component() {
+    const YAML: &str = r#"
+components:
+  - name: Position
+archetypes:
+  - name: Particle
+    components: [Position]
+worlds:
+  - name: Main
+    archetypes: [Particle]
+phases:
+  - name: Update
+systems:
+  - name: Attract
+    phase: Update
+    outputs: [Position]
+    lookup_mut: [Mass]
+"#;
+    let err = match EcsCode::generate(BufReader::new(YAML.as_bytes())) {
+        Ok(_) => panic!("lookup_mut referencing undefined component must fail"),
+        Err(e) => e,
+    };
+    match err {
+        EcsError::MissingComponentInSystem(component, system, suggestion) => {
+            assert_eq!(component, "MassComponent");
+            assert_eq!(system, "AttractSystem");
+            assert_eq!(suggestion, None);
+        }
+        other => panic!("expected MissingComponentInSystem, got {other:?}"),
+    }
+}
+
+/// Neighbor lookups borrow the component from every archetype, including the iterated one,
+/// so they are read-only and may not name an output.
+#[test]
+fn neighbors_borrow_all_archetypes_and_reject_outputs() {
+    const YAML: &str = r#"
+components:
+  - name: Position
+  - name: Velocity
+archetypes:
+  - name: Boid
+    components: [Position, Velocity]
+  - name: Beacon
+    components: [Position]
+worlds:
+  - name: Main
+    archetypes: [Boid, Beacon]
+phases:
+  - name: Update
+systems:
+  - name: Flock
+    phase: Update
+    inputs: [Position]
+    outputs: [Velocity]
+    neighbors: [Position]
+"#;
+    let code = EcsCode::generate(BufReader::new(YAML.as_bytes())).expect("Failed to build ECS");
+    assert!(code.systems.contains("pub struct FlockNeighbors<'a>"));
+    assert!(
+        code.systems
+            .contains("boid_positions: &'a [PositionComponent],")
+    );
+    assert!(
+        code.systems
+            .contains("beacon_positions: &'a [PositionComponent],")
+    );
+    assert!(code.systems.contains("neighbors: &FlockNeighbors<'_>,"));
+
+    let yaml = YAML.replace("neighbors: [Position]", "neighbors: [Velocity]");
+    let result = EcsCode::generate(BufReader::new(yaml.as_bytes()));
+    assert!(matches!(
+        result,
+        Err(EcsError::NeighborLookupOfOutput(component, system))
+            if component == "Velocity" && system == "FlockSystem"
+    ));
+}
+
+#[test]
+fn maybe_components_widen_the_affected_archetypes() {
+    const YAML: &str = r#"
+components:
+  - name: Position
+  - name: Boost
+archetypes:
+  - name: Racer
+    components: [Position, Boost]
+  - name: Rock
+    components: [Position]
+worlds:
+  - name: Main
+    archetypes: [Racer, Rock]
+phases:
+  - name: Update
+systems:
+  - name: Drive
+    phase: Update
+    outputs: [Position]
+    maybe: [Boost]
+"#;
+    let code = EcsCode::generate(BufReader::new(YAML.as_bytes())).expect("Failed to build ECS");
+    assert!(
+        code.systems.contains(
+            r#"const AFFECTED_ARCHETYPES: &'static [&'static str] = &["Racer", "Rock"];"#
+        )
+    );
+    assert!(code.systems.contains("boost: Option<&BoostComponent>,"));
+    assert!(
+        code.world
+            .contains("Some(&self.archetypes.collection.racer.boosts[..]),")
+    );
+
+    let yaml = YAML.replace("outputs: [Position]", "entities: false");
+    let result = EcsCode::generate(BufReader::new(yaml.as_bytes()));
+    assert!(matches!(
+        result,
+        Err(EcsError::MaybeWithoutIteratedComponents(system)) if system == "DriveSystem"
+    ));
+}
+
+#[test]
+fn archetype_order_must_name_distinct_processed_archetypes() {
+    const YAML: &str = r#"
+components:
+  - name: Position
+  - name: Sprite
+  - name: Alpha
+archetypes:
+  - name: Opaque
+    components: [Position, Sprite]
+  - name: Transparent
+    components: [Position, Sprite, Alpha]
+  - name: Marker
+    components: [Position]
+worlds:
+  - name: Main
+    archetypes: [Opaque, Transparent, Marker]
+phases:
+  - name: Render
+systems:
+  - name: Draw
+    phase: Render
+    inputs: [Position, Sprite]
+    archetype_order: [Transparent, Opaque]
+"#;
+    let code = EcsCode::generate(BufReader::new(YAML.as_bytes())).expect("Failed to build ECS");
+    assert!(code.systems.contains(
+        r#"const AFFECTED_ARCHETYPES: &'static [&'static str] = &["Transparent", "Opaque"];"#
+    ));
+
+    // Without a declared order, archetypes are visited by name rather than by declaration.
+    let yaml = YAML
+        .replace("    archetype_order: [Transparent, Opaque]\n", "")
+        .replace("  - name: Opaque\n    components: [Position, Sprite]\n", "")
+        .replace(
+            "  - name: Marker\n",
+            "  - name: Opaque\n    components: [Position, Sprite]\n  - name: Marker\n",
+        );
+    let code = EcsCode::generate(BufReader::new(yaml.as_bytes())).expect("Failed to build ECS");
+    assert!(code.systems.contains(
+        r#"const AFFECTED_ARCHETYPES: &'static [&'static str] = &["Opaque", "Transparent"];"#
+    ));
+
+    let yaml = YAML.replace("[Transparent, Opaque]", "[Transparent, Transparent]");
+    let result = EcsCode::generate(BufReader::new(yaml.as_bytes()));
+    assert!(matches!(
+        result,
+        Err(EcsError::DuplicateArchetypeInOrder(archetype, system))
+            if archetype == "Transparent" && system == "DrawSystem"
+    ));
+
+    let yaml = YAML.replace("[Transparent, Opaque]", "[Marker]");
+    let result = EcsCode::generate(BufReader::new(yaml.as_bytes()));
+    assert!(matches!(
+        result,
+        Err(EcsError::UnmatchedArchetypeInOrder(archetype, system))
+            if archetype == "Marker" && system == "DrawSystem"
+    ));
+}
+
+#[test]
+fn files_match_detects_stale_output() {
+    let file = include_str!("ecs.yaml");
+    let reader = BufReader::new(file.as_bytes());
+    let code = EcsCode::generate(reader).expect("Failed to build ECS");
+
+    let dir = std::env::temp_dir().join(format!("sillyecs-files-match-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).expect("Failed to create output directory");
+    let dir_str = dir
+        .to_str()
+        .expect("Temporary directory is not valid UTF-8");
+
+    assert!(!code.files_match(dir_str).expect("Failed to compare files"));
+
+    code.write_files_to(dir_str).expect("Failed to write files");
+    assert!(code.files_match(dir_str).expect("Failed to compare files"));
+
+    std::fs::write(dir.join("world_gen.rs"), "// stale").expect("Failed to modify file");
+    assert!(!code.files_match(dir_str).expect("Failed to compare files"));
+
+    std::fs::remove_dir_all(&dir).ok();
+}
+
+#[test]
+fn combined_module_contains_sections_in_dependency_order() {
+    let file = include_str!("ecs.yaml");
+    let reader = BufReader::new(file.as_bytes());
+    let code = EcsCode::generate(reader).expect("Failed to build ECS");
+    let combined = code.combined();
+
+    let positions: Vec<_> = ["Components", "Archetypes", "Systems", "World"]
+        .iter()
+        .map(|name| {
+            combined
+                .find(&format!("// ---- {name} ----"))
+                .unwrap_or_else(|| panic!("missing {name} section"))
+        })
+        .collect();
+    assert!(positions.windows(2).all(|pair| pair[0] < pair[1]));
+    for section in [
+        &code.components,
+        &code.archetypes,
+        &code.systems,
+        &code.world,
+    ] {
+        assert!(combined.contains(section.as_str()));
+    }
+
+    let dir = std::env::temp_dir().join(format!("sillyecs-combined-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).expect("Failed to create output directory");
+    let dir_str = dir
+        .to_str()
+        .expect("Temporary directory is not valid UTF-8");
+    code.write_combined_to(dir_str, "ecs_gen.rs")
+        .expect("Failed to write combined file");
+    assert_eq!(
+        std::fs::read_to_string(dir.join("ecs_gen.rs")).expect("Failed to read combined file"),
+        combined
+    );
+    std::fs::remove_dir_all(&dir).ok();
+}
+
+#[test]
+fn naming_replaces_type_name_suffixes() {
+    const YAML: &str = r#"
+naming:
+  component_suffix: Comp
+  archetype_suffix: ""
+  queue_suffix: Commands
+components:
+  - name: Position
+  - name: VelocityComp
+archetypes:
+  - name: Particle
+    components: [Position, VelocityComp]
+worlds:
+  - name: Main
+    archetypes: [Particle]
+    command_queues:
+      - name: Gameplay
+phases:
+  - name: Update
+systems:
+  - name: Move
+    phase: Update
+    inputs: [VelocityComp]
+    outputs: [Position]
+"#;
+    let code = EcsCode::generate(BufReader::new(YAML.as_bytes())).expect("Failed to build ECS");
+
+    assert!(code.components.contains("pub struct PositionComp("));
+    assert!(code.components.contains("pub struct VelocityComp("));
+    assert!(!code.components.contains("PositionComponent"));
+    assert!(code.archetypes.contains("pub struct Particle {"));
+    assert!(code.systems.contains("pub struct MoveSystem("));
+    assert!(code.world.contains("pub struct MainWorld<E, Q>"));
+    assert!(
+        code.world
+            .contains("pub enum GameplayCommands<UserCommand>")
+    );
+}
+
+#[test]
+fn world_alias_defaults_the_phase_events() {
+    let file = include_str!("ecs.yaml");
+    let code = EcsCode::generate(BufReader::new(file.as_bytes())).expect("Failed to build ECS");
+    assert!(
+        code.world
+            .contains("pub type SimpleMainWorld<Q> = MainWorld<NoOpPhaseEvents, Q>;")
+    );
+}
+
+#[test]
+fn world_implements_into_iterator_over_entity_ids() {
+    let file = include_str!("ecs.yaml");
+    let reader = BufReader::new(file.as_bytes());
+    let code = EcsCode::generate(reader).expect("Failed to build ECS");
+
+    assert!(
+        code.world
+            .contains("impl<'a, E, Q> IntoIterator for &'a MainWorld<E, Q>")
+    );
+    assert!(code.world.contains("type IntoIter = EntityIdIter<'a>;"));
+}
+
+#[test]
+fn world_exposes_reserve_and_shrink_to_fit() {
+    let file = include_str!("ecs.yaml");
+    let reader = BufReader::new(file.as_bytes());
+    let code = EcsCode::generate(reader).expect("Failed to build ECS");
+
+    assert!(
+        code.world
+            .contains("pub fn reserve_player(&mut self, additional: usize)")
+    );
+    assert!(code.world.contains("pub fn shrink_to_fit(&mut self)"));
+    assert!(
+        code.archetypes
+            .contains("pub fn reserve(&mut self, additional: usize)")
+    );
+    assert!(code.archetypes.contains("pub fn shrink_to_fit(&mut self)"));
+}
+
+#[test]
+fn systems_expose_typed_item_iterator() {
+    let file = include_str!("ecs.yaml");
+    let reader = BufReader::new(file.as_bytes());
+    let code = EcsCode::generate(reader).expect("Failed to build ECS");
+
+    assert!(code.systems.contains("pub struct PhysicsSystemItem<'a>"));
+    assert!(
+        code.systems
+            .contains("pub position: &'a mut PositionComponent,")
+    );
+    assert!(code.systems.contains("pub fn iter_items<'a>("));
+}
+
+/// Two outputs of a system are iterated through the `ZipMut2` runtime adapter rather than a
+/// nested zip, also when further components are zipped in front of them.
+#[test]
+fn two_outputs_use_zip_mut2() {
+    const YAML: &str = r#"
+components:
+  - name: Position
+  - name: Velocity
+  - name: Health
+archetypes:
+  - name: Particle
+    components: [Position, Velocity, Health]
+worlds:
+  - name: Main
+    archetypes: [Particle]
+phases:
+  - name: Update
+systems:
+  - name: Move
+    phase: Update
+    inputs: [Health]
+    outputs: [Position, Velocity]
+"#;
+
+    let reader = BufReader::new(YAML.as_bytes());
+    let code = EcsCode::generate(reader).expect("generation must succeed");
+    assert!(code.systems.contains(
+        "healths.iter().zip(::sillyecs::ZipMut2::new(&mut positions[..], &mut velocities[..])).map(|(health, (position, velocity))| (health, position, velocity))"
+    ));
+    assert!(
+        !code
+            .systems
+            .contains("positions.iter_mut().zip(velocities.iter_mut())")
+    );
+}
+
+#[test]
+fn phase_has_systems_is_generated() {
+    const YAML: &str = r#"
+components:
+  - name: Position
+archetypes:
+  - name: Particle
+    components: [Position]
+worlds:
+  - name: Main
+    archetypes: [Particle]
+phases:
+  - name: Update
+  - name: Render
+    manual: true
+systems:
+  - name: Tick
+    phase: Update
+    outputs: [Position]
+"#;
+
+    let reader = BufReader::new(YAML.as_bytes());
+    let code = EcsCode::generate(reader).expect("generation must succeed");
+    assert!(
+        code.world
+            .contains("pub const fn phase_has_systems(phase: SystemPhase) -> bool")
+    );
+    assert!(code.world.contains("SystemPhase::Update => true,"));
+    assert!(code.world.contains("SystemPhase::Render => false,"));
+}
+
+const EXTENDS_YAML: &str = r#"
+components:
+  - name: Transform
+  - name: Velocity
+  - name: Health
+archetypes:
+  - name: Base
+    components: [Transform]
+  - name: Mover
+    extends: Base
+    components: [Velocity]
+  - name: Creature
+    extends: Mover
+    components: [Health]
+worlds:
+  - name: Main
+    archetypes: [Base, Mover, Creature]
+phases:
+  - name: Update
+systems:
+  - name: Heal
+    phase: Update
+    outputs: [Health]
+"#;
+
+/// An archetype extending another one ends up with the union of both component lists, also
+/// through a chain of extensions.
+#[test]
+fn archetype_extends_merges_components() {
+    let reader = BufReader::new(EXTENDS_YAML.as_bytes());
+    let code = EcsCode::generate(reader).expect("generation must succeed");
+    let creature = code
+        .archetypes
+        .split("pub struct CreatureArchetype {")
+        .nth(1)
+        .and_then(|rest| rest.split('}').next())
+        .expect("CreatureArchetype struct must be generated");
+    assert!(creature.contains("pub transforms: Vec<TransformComponent>"));
+    assert!(creature.contains("pub velocities: Vec<VelocityComponent>"));
+    assert!(creature.contains("pub healths: Vec<HealthComponent>"));
+}
+
+#[test]
+fn archetype_extends_rejects_duplicate_component() {
+    let yaml = EXTENDS_YAML.replace(
+        "components: [Velocity]",
+        "components: [Velocity, Transform]",
+    );
+    let reader = BufReader::new(yaml.as_bytes());
+    let err = match EcsCode::generate(reader) {
+        Ok(_) => panic!("a component inherited and listed again must fail"),
+        Err(e) => e,
+    };
+    match err {
+        EcsError::DuplicateComponentInArchetype(component, archetype) => {
+            assert_eq!(component, "TransformComponent");
+            assert_eq!(archetype, "MoverArchetype");
+        }
+        other => panic!("expected DuplicateComponentInArchetype, got {other:?}"),
+    }
+}
+
+#[test]
+fn archetype_extends_rejects_cycles() {
+    let yaml = EXTENDS_YAML.replace(
+        "  - name: Base\n    components: [Transform]",
+        "  - name: Base\n    extends: Creature\n    components: [Transform]",
+    );
+    let reader = BufReader::new(yaml.as_bytes());
+    let err = match EcsCode::generate(reader) {
+        Ok(_) => panic!("cyclic extensions must fail"),
+        Err(e) => e,
+    };
+    match err {
+        EcsError::ArchetypeExtensionCycle(path) => {
+            assert_eq!(path, ["Base", "Creature", "Mover", "Base"]);
+        }
+        other => panic!("expected ArchetypeExtensionCycle, got {other:?}"),
+    }
+}
+
+/// Only systems with `context: true` receive the frame context in their hooks.
+#[test]
+fn frame_context_parameter_follows_context_flag() {
+    const YAML: &str = r#"
+components:
+  - name: Position
+archetypes:
+  - name: Particle
+    components: [Position]
+worlds:
+  - name: Main
+    archetypes: [Particle]
+phases:
+  - name: Update
+systems:
+  - name: Timed
+    phase: Update
+    context: true
+    outputs: [Position]
+  - name: Untimed
+    phase: Update
+    inputs: [Position]
+"#;
+
+    let reader = BufReader::new(YAML.as_bytes());
+    let code = EcsCode::generate(reader).expect("generation must succeed");
+    let apply_trait = |name: &str| {
+        code.systems
+            .split(&format!("pub trait Apply{name}: System {{"))
+            .nth(1)
+            .and_then(|rest| rest.split("\n}\n").next())
+            .expect("apply trait must be generated")
+            .to_string()
+    };
+
+    let timed = apply_trait("TimedSystem");
+    let untimed = apply_trait("UntimedSystem");
+    assert_eq!(
+        timed.matches("context: &::sillyecs::FrameContext").count(),
+        timed.matches("    fn ").count()
+    );
+    assert!(!untimed.contains("FrameContext"));
+}
+
+#[test]
+fn frame_plan_expands_fixed_and_conditional_phases() {
+    const YAML: &str = r#"
+components:
+  - name: Position
+archetypes:
+  - name: Particle
+    components: [Position]
+worlds:
+  - name: Main
+    archetypes: [Particle]
+phases:
+  - name: Startup
+    manual: true
+  - name: Physics
+    fixed: 50Hz
+  - name: Update
+    on_request: true
+systems:
+  - name: Integrate
+    phase: Physics
+    outputs: [Position]
+"#;
+
+    let reader = BufReader::new(YAML.as_bytes());
+    let code = EcsCode::generate(reader).expect("generation must succeed");
+    let plan_frame = code
+        .world
+        .split("fn plan_frame(")
+        .nth(1)
+        .and_then(|rest| rest.split("\n    }\n").next())
+        .expect("plan_frame must be generated");
+
+    assert!(!plan_frame.contains("SystemPhase::Startup"));
+    assert!(plan_frame.contains(
+        "Self::phase_batches(SystemPhase::Physics).map(|batch| batch.in_fixed_pass(pass))"
+    ));
+    assert!(plan_frame.contains("if !fixed_only && self.phase_flags.is_update_requested() {"));
+    assert!(plan_frame.contains("batches.extend(Self::phase_batches(SystemPhase::Update));"));
+    assert!(
+        code.world
+            .contains("0 => self.apply_system_group_physics_1(),")
+    );
+    assert!(
+        code.world
+            .contains("pub fn run_frame_batch(&mut self, batch: FrameBatch)")
+    );
+    assert!(
+        code.world
+            .contains("pub fn par_run_frame_batch(&mut self, batch: FrameBatch)")
+    );
+    assert!(code.world.contains("pub fn end_frame(&mut self)"));
+    assert!(
+        code.world
+            .contains("pub fn tick_fixed(&mut self, delta_time_secs: f32)")
+    );
+}
+
+#[test]
+fn misspelled_system_component_suggests_closest_name() {
+    const YAML: &str = r#"
+components:
+  - name: Position
+  - name: Velocity
+archetypes:
+  - name: Particle
+    components: [Position, Velocity]
+worlds:
+  - name: Main
+    archetypes: [Particle]
+phases:
+  - name: Update
+systems:
+  - name: Move
+    phase: Update
+    inputs: [Veloctiy]
+    outputs: [Position]
+"#;
+    let err = match EcsCode::generate(BufReader::new(YAML.as_bytes())) {
+        Ok(_) => panic!("system referencing a misspelled component must fail"),
+        Err(e) => e,
+    };
+    assert!(err.to_string().ends_with("Did you mean 'Velocity'?"));
+    match err {
+        EcsError::MissingComponentInSystem(component, system, suggestion) => {
+            assert_eq!(component, "VeloctiyComponent");
+            assert_eq!(system, "MoveSystem");
+            assert_eq!(suggestion.as_deref(), Some("Velocity"));
+        }
+        other => panic!("expected MissingComponentInSystem, got {other:?}"),
+    }
+}
+
+#[test]
+fn misspelled_archetype_component_suggests_closest_name() {
+    const YAML: &str = r#"
+components:
+  - name: Position
+  - name: Velocity
+archetypes:
+  - name: Particle
+    components: [position, Velocity]
+worlds:
+  - name: Main
+    archetypes: [Particle]
+phases:
+  - name: Update
+systems: []
+"#;
+    let err = match EcsCode::generate(BufReader::new(YAML.as_bytes())) {
+        Ok(_) => panic!("archetype referencing a misspelled component must fail"),
+        Err(e) => e,
+    };
+    match err {
+        EcsError::MissingComponentInArchetype(component, archetype, suggestion) => {
+            assert_eq!(component, "positionComponent");
+            assert_eq!(archetype, "ParticleArchetype");
+            assert_eq!(suggestion.as_deref(), Some("Position"));
+        }
+        other => panic!("expected MissingComponentInArchetype, got {other:?}"),
+    }
+}
+
+#[test]
+fn schema_json_describes_finished_schedule() {
+    const YAML: &str = r#"
+components:
+  - name: Position
+  - name: Velocity
+archetypes:
+  - name: Particle
+    components: [Position, Velocity]
+  - name: Marker
+    components: [Position]
+worlds:
+  - name: Main
+    archetypes: [Particle, Marker]
+phases:
+  - name: Update
+systems:
+  - name: Move
+    phase: Update
+    inputs: [Velocity]
+    outputs: [Position]
+  - name: Render
+    phase: Update
+    inputs: [Position]
+"#;
+
+    let code = EcsCode::generate(BufReader::new(YAML.as_bytes())).expect("generation must succeed");
+    let json = code.to_schema_json();
+    assert_eq!(json, code.to_schema_json());
+
+    let schema: serde_json::Value = serde_json::from_str(&json).expect("schema must be valid JSON");
+    let systems = schema["systems"].as_array().unwrap();
+    let render = systems.iter().find(|s| s["name"] == "Render").unwrap();
+    let marker = schema["archetypes"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|a| a["name"] == "Marker")
+        .unwrap();
+    assert_eq!(
+        render["affected_archetype_ids"].as_array().unwrap().len(),
+        2
+    );
+    assert!(
+        render["affected_archetype_ids"]
+            .as_array()
+            .unwrap()
+            .contains(&marker["id"])
+    );
+    assert_eq!(
+        render["dependencies"][0],
+        serde_json::json!({ "resource": "component", "name": "Position", "access": "read" })
+    );
+
+    let phases = &schema["worlds"][0]["phases"];
+    assert_eq!(phases[0]["phase"], "Update");
+    assert_eq!(
+        phases[0]["batches"],
+        serde_json::json!([["Move"], ["Render"]])
+    );
+}
+
+#[test]
+fn states_know_their_readers_and_writers() {
+    const YAML: &str = r#"
+states:
+  - name: Score
+components:
+  - name: Position
+archetypes:
+  - name: Particle
+    components: [Position]
+worlds:
+  - name: Main
+    archetypes: [Particle]
+phases:
+  - name: Update
+systems:
+  - name: Count
+    phase: Update
+    inputs: [Position]
+    states:
+      - use: Score
+        write: true
+      - use: Score
+        default: write
+  - name: Show
+    phase: Update
+    inputs: [Position]
+    states:
+      - use: Score
+"#;
+
+    let code = EcsCode::generate(BufReader::new(YAML.as_bytes())).expect("generation must succeed");
+    let schema: serde_json::Value =
+        serde_json::from_str(&code.to_schema_json()).expect("schema must be valid JSON");
+    assert_eq!(
+        schema["states"],
+        serde_json::json!([{ "name": "Score", "readers": ["Show"], "writers": ["Count"] }])
+    );
+    assert!(code.world.contains("/// - [`ShowSystem`] (reads)"));
+    assert!(code.world.contains("/// - [`CountSystem`] (writes)"));
+    // The repeated use is deduplicated, so both systems take the state once per hook.
+    assert_eq!(
+        code.systems.matches("score: &mut ScoreState").count(),
+        code.systems.matches("score: &ScoreState").count()
+    );
+
+    let yaml = YAML.replacen("default: write", "default: read", 1);
+    let result = EcsCode::generate(BufReader::new(yaml.as_bytes()));
+    assert!(matches!(
+        result,
+        Err(EcsError::ConflictingStateUse(state, system)) if state == "Score" && system == "CountSystem"
+    ));
+}
+
+#[test]
+fn archetype_storage_defaults_to_vec_and_can_be_replaced() {
+    const YAML: &str = r#"
+components:
+  - name: Position
+  - name: Health
+archetypes:
+  - name: Particle
+    components: [Position]
+    promotions: [LivingParticle]
+  - name: LivingParticle
+    components: [Position, Health]
+worlds:
+  - name: Main
+    archetypes: [Particle, LivingParticle]
+phases:
+  - name: Update
+systems: []
+"#;
+
+    let code = EcsCode::generate(BufReader::new(YAML.as_bytes())).expect("generation must succeed");
+    assert!(
+        code.archetypes
+            .contains("pub positions: Vec<PositionComponent>,")
+    );
+    assert!(code.archetypes.contains("healths: Vec<HealthComponent>,"));
+    assert!(
+        code.archetypes
+            .contains("::sillyecs::ComponentStorage::push(&mut self.positions, position);")
+    );
+
+    let yaml = format!("storage: my_crate::Pooled\n{YAML}");
+    let code = EcsCode::generate(BufReader::new(yaml.as_bytes())).expect("generation must succeed");
+    assert!(!code.archetypes.contains("Vec<PositionComponent>"));
+    assert!(
+        code.archetypes
+            .contains("pub positions: my_crate::Pooled<PositionComponent>,")
+    );
+    assert!(
+        code.archetypes
+            .contains("healths: my_crate::Pooled<HealthComponent>,")
+    );
+    assert!(
+        code.archetypes
+            .contains("pub entities: Vec<::sillyecs::EntityId>,")
+    );
+}
+
+#[test]
+fn promotions_take_added_components_as_parameters() {
+    // `File` has no `Default`, so the promotion cannot make up the added component itself.
+    const YAML: &str = r#"
+components:
+  - name: Position
+  - name: Log
+    rust_type: std::fs::File
+archetypes:
+  - name: Particle
+    components: [Position]
+    promotions: [LoggedParticle]
+  - name: LoggedParticle
+    components: [Position, Log]
+worlds:
+  - name: Main
+    archetypes: [Particle, LoggedParticle]
+phases:
+  - name: Update
+systems: []
+"#;
+
+    let code = EcsCode::generate(BufReader::new(YAML.as_bytes())).expect("generation must succeed");
+    assert!(code.archetypes.contains(
+        "pub fn promote_to_logged_particles(\n        self,\n        logs: Vec<std::fs::File>,\n    )"
+    ));
+    assert!(code.archetypes.contains(
+        "pub fn from_particle(\n        source: ParticleEntityData,\n        log: std::fs::File,\n    )"
+    ));
+}
+
+#[test]
+fn systems_skip_iteration_over_empty_archetypes_unless_always_run() {
+    const YAML: &str = r#"
+components:
+  - name: Position
+  - name: Velocity
+archetypes:
+  - name: Particle
+    components: [Position, Velocity]
+worlds:
+  - name: Main
+    archetypes: [Particle]
+phases:
+  - name: Update
+systems:
+  - name: Move
+    phase: Update
+    inputs: [Velocity]
+    outputs: [Position]
+  - name: Flush
+    phase: Update
+    always_run: true
+    inputs: [Position]
+"#;
+
+    let code = EcsCode::generate(BufReader::new(YAML.as_bytes())).expect("generation must succeed");
+    assert!(
+        code.world
+            .contains("let has_entities = !self.archetypes.collection.particle.is_empty();")
+    );
+    // The parallel path only exists in multithreaded builds.
+    if !cfg!(feature = "single-threaded") {
+        assert!(
+            code.world.contains(
+                "let has_move_entities = !self.archetypes.collection.particle.is_empty();"
+            )
+        );
+    }
+    assert!(!code.world.contains("let has_flush_entities"));
+}
+
+/// The typed `ID` consts of components, archetypes, and systems are derived from the declaration
+/// order, so downstream code may cache them. Generating an unrelated ECS in between must not
+/// shift them.
+#[test]
+fn id_consts_are_stable_across_generations() {
+    const YAML: &str = r#"
+components:
+  - name: Position
+  - name: Velocity
+archetypes:
+  - name: Particle
+    components: [Position, Velocity]
+  - name: Static
+    components: [Position]
+worlds:
+  - name: Main
+    archetypes: [Particle, Static]
+phases:
+  - name: Update
+systems:
+  - name: Move
+    phase: Update
+    inputs: [Velocity]
+    outputs: [Position]
+  - name: Settle
+    phase: Update
+    inputs: [Position]
+"#;
+
+    let first = EcsCode::generate(BufReader::new(YAML.as_bytes())).expect("first generate");
+    EcsCode::generate(BufReader::new(include_str!("ecs.yaml").as_bytes()))
+        .expect("unrelated generate");
+    let second = EcsCode::generate(BufReader::new(YAML.as_bytes())).expect("second generate");
+
+    for code in [&first, &second] {
+        assert!(
+            code.components
+                .contains("const ID: ComponentId = ComponentId::Velocity;")
+        );
+        assert!(code.components.contains("    Velocity = 2,"));
+        assert!(
+            code.archetypes
+                .contains("const ID: ArchetypeId = ArchetypeId::Static;")
+        );
+        assert!(code.archetypes.contains("    Static = 2,"));
+        assert!(
+            code.systems
+                .contains("const ID: SystemId = SystemId::Settle;")
+        );
+        assert!(code.systems.contains("    Settle = 2,"));
+    }
+    assert_eq!(first.components, second.components);
+    assert_eq!(first.archetypes, second.archetypes);
+    assert_eq!(first.systems, second.systems);
+}
+
+#[test]
+fn component_mut_where_checks_each_archetype_of_the_component() {
+    const YAML: &str = r#"
+components:
+  - name: Velocity
+  - name: Mass
+  - name: Sprite
+archetypes:
+  - name: Particle
+    components: [Velocity]
+  - name: Body
+    components: [Velocity, Mass]
+  - name: Decal
+    components: [Sprite]
+worlds:
+  - name: Main
+    archetypes: [Particle, Body, Decal]
+phases:
+  - name: Update
+systems: []
+"#;
+
+    let code = EcsCode::generate(BufReader::new(YAML.as_bytes())).expect("generation must succeed");
+    let start = code
+        .world
+        .find("pub fn velocities_mut_where<C: Component>(&mut self) -> ::sillyecs::FlattenSlicesMut<'_, VelocityComponent> {")
+        .expect("one filtered accessor is generated per component");
+    let end = start + code.world[start..].find("])").unwrap();
+    let body = &code.world[start..end];
+    assert!(body.contains("&mut self.archetypes.collection.body.velocities[..]"));
+    assert!(body.contains("&mut self.archetypes.collection.particle.velocities[..]"));
+    assert!(!body.contains("collection.decal"));
+    assert!(!code.world.contains("_mut_where_"));
+}
+
+/// Malformed YAML used to panic inside `EcsCode::generate`; it must surface as an error instead.
+#[test]
+fn malformed_definition_is_an_error_not_a_panic() {
+    const YAML: &str = r#"
+components:
+  - name: Position
+archetypes:
+  - name: Particle
+    components: Position
+"#;
+
+    let result = std::panic::catch_unwind(|| EcsCode::generate(BufReader::new(YAML.as_bytes())));
+    let result = result.expect("generate must not panic");
+    assert!(matches!(result, Err(EcsError::InvalidDefinition(_))));
+
+    let code = EcsCode::generate(BufReader::new(include_str!("ecs.yaml").as_bytes()))
+        .expect("generation must succeed");
+    assert!(code.diagnostics().is_empty());
+}
+
+/// Systems whose data dependencies form a cycle are still scheduled; the dropped ordering is
+/// reported as a diagnostic instead of failing the build.
+#[test]
+fn broken_dependency_cycle_is_reported_as_diagnostic() {
+    const YAML: &str = r#"
+components:
+  - name: X
+  - name: Y
+  - name: Z
+archetypes:
+  - name: Thing
+    components: [X, Y, Z]
+worlds:
+  - name: Main
+    archetypes: [Thing]
+phases:
+  - name: Update
+systems:
+  - name: Alpha
+    phase: Update
+    inputs: [Z]
+    outputs: [X]
+  - name: Beta
+    phase: Update
+    inputs: [X]
+    outputs: [Y]
+  - name: Gamma
+    phase: Update
+    inputs: [Y]
+    outputs: [Z]
+"#;
+
+    let code = EcsCode::generate(BufReader::new(YAML.as_bytes())).expect("generation must succeed");
+    assert_eq!(code.diagnostics().len(), 1, "{:?}", code.diagnostics());
+    let diagnostic = &code.diagnostics()[0];
+    assert!(
+        diagnostic.starts_with("sillyecs scheduler: cycle detected through systems ["),
+        "{diagnostic}"
+    );
+    for system in ["Alpha", "Beta", "Gamma"] {
+        assert!(diagnostic.contains(system), "{diagnostic}");
+    }
+    assert!(
+        diagnostic.ends_with("dropping edge Gamma -> Alpha"),
+        "{diagnostic}"
+    );
+}
+
+#[test]
+fn ecs_without_archetypes_is_a_descriptive_error() {
+    const YAML: &str = r#"
+components: []
+archetypes: []
+phases: []
+worlds: []
+"#;
+
+    let result = EcsCode::generate(BufReader::new(YAML.as_bytes()));
+    assert!(matches!(result, Err(EcsError::NoArchetypes)));
+}
+
+#[test]
+fn empty_rust_type_is_rejected() {
+    const YAML: &str = r#"
+components:
+  - name: Position
+  - name: Waveform
+    rust_type: "  "
+archetypes:
+  - name: Signal
+    components: [Position, Waveform]
+phases: []
+worlds: []
+"#;
+
+    let result = EcsCode::generate(BufReader::new(YAML.as_bytes()));
+    assert!(matches!(result, Err(EcsError::EmptyRustType(name)) if name == "Waveform"));
+}
+
+#[test]
+fn aligned_components_use_aligned_storage() {
+    const YAML: &str = r#"
+storage: my_crate::PooledVec
+components:
+  - name: Position
+  - name: Velocity
+    align: 32
+archetypes:
+  - name: Particle
+    components: [Position, Velocity]
+phases: []
+worlds: []
+"#;
+
+    let code = EcsCode::generate(BufReader::new(YAML.as_bytes())).expect("Failed to build ECS");
+    assert!(
+        code.archetypes
+            .contains("pub positions: my_crate::PooledVec<PositionComponent>,")
+    );
+    assert!(
+        code.archetypes
+            .contains("pub velocities: ::sillyecs::AlignedVec<VelocityComponent, 32>,")
+    );
+
+    let yaml = YAML.replace("align: 32", "align: 24");
+    let result = EcsCode::generate(BufReader::new(yaml.as_bytes()));
+    assert!(matches!(
+        result,
+        Err(EcsError::InvalidComponentAlignment(name, 24)) if name == "Velocity"
+    ));
+}
+
+#[test]
+fn component_fields_are_reflected_unless_rust_type_is_used() {
+    const YAML: &str = r#"
+components:
+  - name: Position
+    fields:
+      - { name: x, type: f32 }
+      - { name: label, type: "Option<String>" }
+archetypes:
+  - name: Particle
+    components: [Position]
+phases: []
+worlds: []
+"#;
+
+    let code = EcsCode::generate(BufReader::new(YAML.as_bytes())).expect("Failed to build ECS");
+    assert!(code.components.contains(
+        "pub const FIELDS: &'static [(&'static str, &'static str)] = &[\n        (\"x\", \"f32\"),\n        (\"label\", \"Option<String>\"),\n    ];"
+    ));
+
+    let yaml = YAML.replace("fields:", "rust_type: Position2D\n    fields:");
+    let result = EcsCode::generate(BufReader::new(yaml.as_bytes()));
+    assert!(matches!(result, Err(EcsError::FieldsWithRustType(name)) if name == "Position"));
+
+    let yaml = YAML.replace("name: label", "name: x");
+    let result = EcsCode::generate(BufReader::new(yaml.as_bytes()));
+    assert!(matches!(
+        result,
+        Err(EcsError::DuplicateComponentField(field, name)) if field == "x" && name == "Position"
+    ));
+}
+
+#[test]
+fn finished_ecs_round_trips_to_identical_code() {
+    let source = include_str!("ecs.yaml");
+    let code =
+        EcsCode::generate(BufReader::new(source.as_bytes())).expect("generation must succeed");
+
+    let json = code.finished().to_json();
+    let finished = FinishedEcs::from_json(&json).expect("the cache must load");
+    assert_eq!(&finished, code.finished());
+    assert!(finished.is_current_for(source));
+    assert!(!finished.is_current_for(&format!("{source}\n# changed")));
+
+    // The `single-threaded` feature is applied while finishing, so toggling it invalidates the cache.
+    let enabled = cfg!(feature = "single-threaded");
+    let toggled = json.replace(
+        &format!("\"single_threaded_feature\":{enabled}"),
+        &format!("\"single_threaded_feature\":{}", !enabled),
+    );
+    assert_ne!(toggled, json);
+    let toggled = FinishedEcs::from_json(&toggled).expect("the cache must load");
+    assert!(!toggled.is_current_for(source));
+
+    let cached = EcsCode::from_finished(&finished).expect("rendering must succeed");
+    assert_eq!(cached.components, code.components);
+    assert_eq!(cached.archetypes, code.archetypes);
+    assert_eq!(cached.systems, code.systems);
+    assert_eq!(cached.world, code.world);
+    assert_eq!(cached.to_schema_json(), code.to_schema_json());
+    assert_eq!(cached.diagnostics(), code.diagnostics());
+
+    assert!(matches!(
+        FinishedEcs::from_json("{"),
+        Err(EcsError::InvalidFinishedEcs(_))
+    ));
+}
+
+#[test]
+fn streamed_code_matches_rendered_code() {
+    let source = include_str!("ecs.yaml");
+    let code =
+        EcsCode::generate(BufReader::new(source.as_bytes())).expect("generation must succeed");
+
+    let mut writers = CodeWriters::<Vec<u8>>::default();
+    EcsCode::render_to_writers(code.finished(), &mut writers).expect("rendering must succeed");
+    assert_eq!(writers.components, code.components.as_bytes());
+    assert_eq!(writers.archetypes, code.archetypes.as_bytes());
+    assert_eq!(writers.systems, code.systems.as_bytes());
+    assert_eq!(writers.world, code.world.as_bytes());
+}
+
+const PHASE_ORDER_YAML: &str = r#"
+components:
+  - name: Position
+archetypes:
+  - name: Particle
+    components: [Position]
+worlds:
+  - name: Main
+    archetypes: [Particle]
+phases:
+  - name: Render
+    run_after: [Update]
+  - name: Update
+    run_after: [Input]
+  - name: Input
+"#;
+
+#[test]
+fn phases_run_in_declared_order() {
+    let code = EcsCode::generate(BufReader::new(PHASE_ORDER_YAML.as_bytes()))
+        .expect("generation must succeed");
+
+    let frame = code
+        .world
+        .split("pub fn apply_system_phases(&mut self)")
+        .nth(1)
+        .expect("apply_system_phases is generated");
+    let position = |phase: &str| {
+        frame
+            .find(&format!("self.apply_system_phase_{phase}();"))
+            .unwrap_or_else(|| panic!("phase {phase} is applied"))
+    };
+    assert!(position("input") < position("update"));
+    assert!(position("update") < position("render"));
+}
+
+#[test]
+fn phase_ordering_cycle_is_an_error() {
+    let yaml = PHASE_ORDER_YAML.replace(
+        "  - name: Input\n",
+        "  - name: Input\n    run_after: [Render]\n",
+    );
+    let result = EcsCode::generate(BufReader::new(yaml.as_bytes()));
+    let Err(EcsError::PhaseOrderingCycle(path)) = result else {
+        panic!("expected a phase ordering cycle");
+    };
+    assert_eq!(path.len(), 4);
+    assert_eq!(path.first(), path.last());
+
+    let yaml = PHASE_ORDER_YAML.replace("run_after: [Input]", "run_after: [Draw]");
+    let result = EcsCode::generate(BufReader::new(yaml.as_bytes()));
+    assert!(matches!(
+        result,
+        Err(EcsError::MissingPhaseDependency(dependency, phase)) if dependency == "Draw" && phase == "Update"
+    ));
+}
+
+#[test]
+fn descriptions_render_as_doc_comments() {
+    const YAML: &str = "
+components:
+  - name: Position
+    description: |
+      Component description.
+      Second component line.
+archetypes:
+  - name: Particle
+    description: Archetype description.
+    components: [Position]
+worlds:
+  - name: Main
+    description: |
+      World description.
+
+      Second world paragraph.
+    archetypes: [Particle]
+phases:
+  - name: Update
+    description: Phase description.
+systems:
+  - name: Tick
+    description: System description.
+    phase: Update
+    outputs: [Position]
+";
+
+    let code = EcsCode::generate(BufReader::new(YAML.as_bytes())).expect("Failed to build ECS");
+
+    assert!(code.components.contains(
+        "/// Component description.\n/// Second component line.\n///\n/// See also [`PositionData`]"
+    ));
+    assert!(
+        code.components
+            .contains("    /// Component description.\n    /// Second component line.\n")
+    );
+    assert!(code.archetypes.contains("/// Archetype description.\n"));
+    assert!(code.systems.contains("/// System description.\n"));
+    assert!(code.systems.contains("    /// Phase description.\n"));
+    assert!(code.world.contains(
+        "/// World description.\n///\n/// Second world paragraph.\n///\n/// # Type parameters\n"
+    ));
+    assert!(code.world.contains(
+        "///   are handled between phases.\n#[derive(Debug)]\npub struct MainWorld<E, Q>"
+    ));
+}
+
+const REQUIRED_COMPANION_YAML: &str = r#"
+components:
+  - name: Position
+  - name: Velocity
+    requires: [Position]
+archetypes:
+  - name: Particle
+    components: [Position, Velocity]
+worlds:
+  - name: Main
+    archetypes: [Particle]
+phases: []
+"#;
+
+#[test]
+fn required_companion_components_are_validated() {
+    EcsCode::generate(BufReader::new(REQUIRED_COMPANION_YAML.as_bytes()))
+        .expect("generation must succeed");
+
+    let yaml = REQUIRED_COMPANION_YAML.replace("[Position, Velocity]", "[Velocity]");
+    let result = EcsCode::generate(BufReader::new(yaml.as_bytes()));
+    assert!(matches!(
+        result,
+        Err(EcsError::MissingRequiredCompanionComponent(archetype, component, required))
+            if archetype == "ParticleArchetype" && component == "VelocityComponent" && required == "PositionComponent"
+    ));
+
+    let yaml = REQUIRED_COMPANION_YAML.replace("requires: [Position]", "requires: [Positon]");
+    let result = EcsCode::generate(BufReader::new(yaml.as_bytes()));
+    assert!(matches!(
+        result,
+        Err(EcsError::MissingRequiredComponent(_, _, Some(hint))) if hint == "Position"
+    ));
+}
+
+const DISABLED_SYSTEM_YAML: &str = r#"
+components:
+  - name: Position
+  - name: Velocity
+archetypes:
+  - name: Particle
+    components: [Position, Velocity]
+worlds:
+  - name: Main
+    archetypes: [Particle]
+phases:
+  - name: Update
+systems:
+  - name: Gravity
+    phase: Update
+    enabled: false
+    outputs: [Velocity]
+  - name: Integrate
+    phase: Update
+    inputs: [Velocity]
+    outputs: [Position]
+"#;
+
+#[test]
+fn disabled_systems_are_not_generated() {
+    let code = EcsCode::generate(BufReader::new(DISABLED_SYSTEM_YAML.as_bytes()))
+        .expect("generation must succeed");
+
+    assert!(code.systems.contains("IntegrateSystem"));
+    assert!(code.world.contains("IntegrateSystem"));
+    for generated in [
+        &code.components,
+        &code.archetypes,
+        &code.systems,
+        &code.world,
+    ] {
+        assert!(!generated.contains("Gravity"));
+    }
+    assert!(!code.to_schema_json().contains("Gravity"));
+}
+
+#[test]
+fn run_after_disabled_system_is_an_error() {
+    let yaml = DISABLED_SYSTEM_YAML.replace(
+        "    inputs: [Velocity]\n",
+        "    inputs: [Velocity]\n    run_after: [Gravity]\n",
+    );
+    let result = EcsCode::generate(BufReader::new(yaml.as_bytes()));
+    assert!(matches!(
+        result,
+        Err(EcsError::DependencyOnDisabledSystem(dependency, system)) if dependency == "Gravity" && system == "IntegrateSystem"
+    ));
+}
+
+#[test]
+fn duplicate_command_queue_is_rejected() {
+    const YAML: &str = r#"
+components:
+  - name: Position
+archetypes:
+  - name: Marker
+    components: [Position]
+worlds:
+  - name: Main
+    archetypes: [Marker]
+    command_queues:
+      - name: Gameplay
+      - name: Gameplay
+        drain: frame
+phases: []
+systems: []
+"#;
+
+    let result = EcsCode::generate(BufReader::new(YAML.as_bytes()));
+    assert!(matches!(
+        result,
+        Err(EcsError::DuplicateCommandQueue(queue, world)) if queue == "Gameplay" && world == "Main"
+    ));
+
+    let reserved = YAML.replacen("name: Gameplay", "name: Default", 1);
+    let result = EcsCode::generate(BufReader::new(reserved.as_bytes()));
+    assert!(matches!(
+        result,
+        Err(EcsError::DuplicateCommandQueue(queue, _)) if queue == "Default"
+    ));
+}
+
+const SYSTEM_SETS_YAML: &str = r#"
+components:
+  - name: Position
+  - name: Velocity
+archetypes:
+  - name: Particle
+    components: [Position, Velocity]
+worlds:
+  - name: Main
+    archetypes: [Particle]
+phases:
+  - name: Update
+systems:
+  - name: Integrate
+    phase: Update
+    sets: [physics]
+    inputs: [Velocity]
+    outputs: [Position]
+  - name: Think
+    phase: Update
+    sets: [ai]
+    inputs: [Position]
+set_ordering:
+  - [physics, ai]
+"#;
+
+#[test]
+fn set_ordering_orders_systems_of_the_sets() {
+    let code = EcsCode::generate(BufReader::new(SYSTEM_SETS_YAML.as_bytes()))
+        .expect("generation must succeed");
+    let schema: serde_json::Value =
+        serde_json::from_str(&code.to_schema_json()).expect("schema must be valid JSON");
+    let think = schema["systems"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|s| s["name"] == "Think")
+        .unwrap();
+    assert_eq!(think["sets"], serde_json::json!(["ai"]));
+    assert_eq!(
+        schema["worlds"][0]["phases"][0]["batches"],
+        serde_json::json!([["Integrate"], ["Think"]])
+    );
+
+    // The set ordering wins over the resource order, just like `run_after`.
+    let reversed = SYSTEM_SETS_YAML.replace("[physics, ai]", "[ai, physics]");
+    let code =
+        EcsCode::generate(BufReader::new(reversed.as_bytes())).expect("generation must succeed");
+    let schema: serde_json::Value =
+        serde_json::from_str(&code.to_schema_json()).expect("schema must be valid JSON");
+    assert_eq!(
+        schema["worlds"][0]["phases"][0]["batches"],
+        serde_json::json!([["Think"], ["Integrate"]])
+    );
+
+    let contradicting =
+        SYSTEM_SETS_YAML.replace("  - [physics, ai]", "  - [physics, ai]\n  - [ai, physics]");
+    let result = EcsCode::generate(BufReader::new(contradicting.as_bytes()));
+    assert!(matches!(result, Err(EcsError::ForcedOrderingCycle(_))));
+}
+
+#[test]
+fn set_ordering_rejects_invalid_set_names() {
+    let undefined = SYSTEM_SETS_YAML.replace("[physics, ai]", "[physics, rendering]");
+    let result = EcsCode::generate(BufReader::new(undefined.as_bytes()));
+    assert!(matches!(result, Err(EcsError::UndefinedSystemSet(set)) if set == "rendering"));
+
+    let itself = SYSTEM_SETS_YAML.replace("[physics, ai]", "[ai, ai]");
+    let result = EcsCode::generate(BufReader::new(itself.as_bytes()));
+    assert!(matches!(result, Err(EcsError::SystemSetOrderedAgainstItself(set)) if set == "ai"));
+}
@@ -10,18 +10,24 @@
 //! - `user.rs`  - hand-written user-side stubs (component data, system data,
 //!   `Apply<X>System` impls, `WorldCommandQueue` impl, `EntityLocationMap`
 //!   alias).
+//! - `tests.rs` - optional runtime assertions against the generated code,
+//!   compiled into the fixture crate as a `#[cfg(test)]` module.
 //!
 //! The test renders the four template outputs into the fixture crate at
 //! `target/sillyecs-compile-fixtures/<name>/` (a stable workspace path, not a
 //! system tempdir, so cargo's incremental cache survives across runs), then
-//! shells out to `cargo check` against that crate. A non-zero exit prints the
+//! shells out to `cargo test` against that crate, which both type-checks the
+//! generated code and runs the fixture's `tests.rs` (if any). A non-zero exit prints the
 //! captured stderr and leaves the fixture directory on disk for inspection.
+//!
+//! Fixtures may also be rendered with an edited `user.rs` to assert that broken user code
+//! fails to compile with the expected diagnostics.
 
 use sillyecs_build::EcsCode;
 use std::fs;
 use std::io::BufReader;
 use std::path::{Path, PathBuf};
-use std::process::Command;
+use std::process::{Command, Output};
 
 const FIXTURE_ROOT: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures");
 
@@ -35,16 +41,255 @@ fn full_coverage_fixture_compiles() {
     run_fixture("full_coverage");
 }
 
+#[test]
+fn custom_storage_fixture_compiles() {
+    run_fixture("custom_storage");
+}
+
+#[test]
+fn no_systems_fixture_compiles() {
+    run_fixture("no_systems");
+}
+
+#[test]
+fn multi_world_fixture_compiles() {
+    run_fixture("multi_world");
+}
+
+#[test]
+fn rust_type_fixture_compiles() {
+    run_fixture("rust_type");
+}
+
+#[test]
+fn single_threaded_fixture_compiles() {
+    run_fixture("single_threaded");
+}
+
+#[test]
+fn command_queues_fixture_compiles() {
+    run_fixture("command_queues");
+}
+
+#[test]
+fn neighbors_fixture_compiles() {
+    run_fixture("neighbors");
+}
+
+#[test]
+fn maybe_fixture_compiles() {
+    run_fixture("maybe");
+}
+
+/// The combined module must compile on its own, included with a single `include!`.
+#[test]
+fn combined_module_compiles() {
+    let crate_dir = write_fixture_crate(
+        "full_coverage",
+        "full_coverage_combined",
+        |yaml| yaml,
+        |user_rs| user_rs,
+    );
+    assert!(LIB_RS.contains(SEPARATE_INCLUDES));
+    fs::write(
+        crate_dir.join("src").join("lib.rs"),
+        LIB_RS.replace(SEPARATE_INCLUDES, "include!(\"generated/ecs_gen.rs\");\n"),
+    )
+    .unwrap();
+
+    let output = run_cargo(&crate_dir, "test");
+    assert!(
+        output.status.success(),
+        "combined module failed to compile or its tests failed:\n{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+}
+
+/// Without `single_threaded`, the generated traits require `Send + Sync`, so the `!Send` data of
+/// the `single_threaded` fixture must be rejected.
+#[test]
+#[cfg(not(feature = "single-threaded"))]
+fn non_send_data_requires_single_threaded() {
+    let crate_dir = write_fixture_crate(
+        "single_threaded",
+        "single_threaded_disabled",
+        |yaml| yaml.replace("single_threaded: true", "single_threaded: false"),
+        |user_rs| user_rs,
+    );
+
+    let output = run_cargo(&crate_dir, "check");
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        !output.status.success(),
+        "`!Send` components must not compile without single_threaded"
+    );
+    assert!(
+        stderr.contains("cannot be sent between threads safely"),
+        "missing Send diagnostic:\n{stderr}"
+    );
+}
+
+/// Omitting a system implementation must fail at `assert_all_systems_implemented` with a
+/// message that names the missing system, rather than only with scattered trait-bound errors.
+#[test]
+fn missing_system_impl_is_reported_by_assertion() {
+    let crate_dir = write_fixture_crate(
+        "full_coverage",
+        "full_coverage_missing_impl",
+        |yaml| yaml,
+        |user_rs| {
+            let start = user_rs
+                .find("impl ApplyDampSystem for DampSystem {")
+                .expect("fixture implements ApplyDampSystem");
+            let end = start
+                + user_rs[start..]
+                    .find("\n}\n")
+                    .expect("impl block is closed")
+                + 3;
+            format!("{}{}", &user_rs[..start], &user_rs[end..])
+        },
+    );
+
+    let output = run_cargo(&crate_dir, "check");
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        !output.status.success(),
+        "fixture without ApplyDampSystem must not compile"
+    );
+    assert!(
+        stderr.contains(
+            "the `Damp` system is declared in the ECS schema, but `DampSystem` does not implement `ApplyDampSystem`"
+        ),
+        "missing diagnostic message:\n{stderr}"
+    );
+    assert!(
+        stderr.contains("required by a bound in `assert_all_systems_implemented`"),
+        "missing pointer to the assertion:\n{stderr}"
+    );
+}
+
+/// Spawn methods are `#[must_use]`, so dropping the returned ID fails under
+/// `#[deny(unused_must_use)]`.
+#[test]
+fn dropped_spawn_result_is_reported() {
+    let crate_dir = write_fixture_crate(
+        "no_systems",
+        "no_systems_dropped_spawn",
+        |yaml| yaml,
+        |user_rs| {
+            format!(
+                "{user_rs}\n\
+                 #[deny(unused_must_use)]\n\
+                 pub fn spawn_and_forget(world: &mut MainWorld<NoOpPhaseEvents, NoCommands>) {{\n\
+                 \x20   world.spawn_marker(MarkerEntityComponents {{\n\
+                 \x20       position: PositionComponent::new(PositionData::default()),\n\
+                 \x20   }});\n\
+                 }}\n"
+            )
+        },
+    );
+
+    let output = run_cargo(&crate_dir, "check");
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        !output.status.success(),
+        "dropping a spawned entity ID must not compile under deny(unused_must_use)"
+    );
+    assert!(
+        stderr.contains("the returned ID is the only way to address the spawned entity"),
+        "missing must_use diagnostic:\n{stderr}"
+    );
+}
+
+/// Components larger than `max_component_size` fail to compile unless marked as `large`.
+#[test]
+fn oversized_component_requires_large_marker() {
+    let crate_dir = write_fixture_crate(
+        "no_systems",
+        "no_systems_oversized_component",
+        |yaml| format!("max_component_size: 2\n{yaml}"),
+        |user_rs| user_rs,
+    );
+
+    let output = run_cargo(&crate_dir, "check");
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        !output.status.success(),
+        "a component exceeding max_component_size must not compile"
+    );
+    assert!(
+        stderr.contains(
+            "the `Position` component exceeds 2 bytes; box its data or mark it as `large: true`"
+        ),
+        "missing size diagnostic:\n{stderr}"
+    );
+
+    let crate_dir = write_fixture_crate(
+        "no_systems",
+        "no_systems_large_component",
+        |yaml| {
+            format!("max_component_size: 2\n{yaml}").replace(
+                "  - name: Position\n",
+                "  - name: Position\n    large: true\n",
+            )
+        },
+        |user_rs| user_rs,
+    );
+
+    let output = run_cargo(&crate_dir, "check");
+    assert!(
+        output.status.success(),
+        "a large component must compile:\n{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+}
+
 fn run_fixture(fixture_name: &str) {
+    let crate_dir = write_fixture_crate(fixture_name, fixture_name, |yaml| yaml, |user_rs| user_rs);
+    let output = run_cargo(&crate_dir, "test");
+
+    if !output.status.success() {
+        panic!(
+            "generated code from fixture `{fixture_name}` failed to compile or its tests failed.\n\
+             crate at: {}\n\
+             --- stdout ---\n{}\n--- stderr ---\n{}",
+            crate_dir.display(),
+            String::from_utf8_lossy(&output.stdout),
+            String::from_utf8_lossy(&output.stderr),
+        );
+    }
+}
+
+/// Renders a fixture into the crate `target/sillyecs-compile-fixtures/<crate_name>/`, passing the
+/// fixture's `ecs.yaml` and `user.rs` through `edit_yaml` and `edit_user_rs` first, and returns
+/// the crate directory.
+fn write_fixture_crate(
+    fixture_name: &str,
+    crate_name: &str,
+    edit_yaml: impl FnOnce(String) -> String,
+    edit_user_rs: impl FnOnce(String) -> String,
+) -> PathBuf {
     let fixture_dir = PathBuf::from(FIXTURE_ROOT).join(fixture_name);
     let yaml_path = fixture_dir.join("ecs.yaml");
     let user_path = fixture_dir.join("user.rs");
 
-    let yaml = fs::read(&yaml_path).unwrap_or_else(|e| panic!("read {}: {e}", yaml_path.display()));
-    let user_rs = fs::read_to_string(&user_path)
-        .unwrap_or_else(|e| panic!("read {}: {e}", user_path.display()));
+    let yaml = edit_yaml(
+        fs::read_to_string(&yaml_path)
+            .unwrap_or_else(|e| panic!("read {}: {e}", yaml_path.display())),
+    );
+    let user_rs = edit_user_rs(
+        fs::read_to_string(&user_path)
+            .unwrap_or_else(|e| panic!("read {}: {e}", user_path.display())),
+    );
+    let tests_path = fixture_dir.join("tests.rs");
+    let tests_rs = if tests_path.exists() {
+        fs::read_to_string(&tests_path)
+            .unwrap_or_else(|e| panic!("read {}: {e}", tests_path.display()))
+    } else {
+        String::new()
+    };
 
-    let code = EcsCode::generate(BufReader::new(&yaml[..]))
+    let code = EcsCode::generate(BufReader::new(yaml.as_bytes()))
         .unwrap_or_else(|e| panic!("EcsCode::generate failed for {fixture_name}: {e:?}"));
 
     // Stable, per-fixture workspace location so cargo's incremental cache
@@ -53,13 +298,13 @@ fn run_fixture(fixture_name: &str) {
     let workspace_target = workspace_target_dir();
     let crate_dir = workspace_target
         .join("sillyecs-compile-fixtures")
-        .join(fixture_name);
+        .join(crate_name);
     let src_dir = crate_dir.join("src");
     let generated_dir = src_dir.join("generated");
 
     // Wipe the fixture crate before writing it. Silently ignoring a failed
     // deletion would let stale files from a previous run survive into the
-    // next `cargo check`, which could mask a regression by compiling the old
+    // next `cargo test`, which could mask a regression by compiling the old
     // state. Propagate the error so the test fails loudly instead.
     if crate_dir.exists() {
         fs::remove_dir_all(&crate_dir)
@@ -71,15 +316,25 @@ fn run_fixture(fixture_name: &str) {
     fs::write(generated_dir.join("archetypes_gen.rs"), &code.archetypes).unwrap();
     fs::write(generated_dir.join("systems_gen.rs"), &code.systems).unwrap();
     fs::write(generated_dir.join("world_gen.rs"), &code.world).unwrap();
+    code.write_combined_to(
+        generated_dir.to_str().expect("UTF-8 fixture path"),
+        "ecs_gen.rs",
+    )
+    .unwrap();
 
     fs::write(src_dir.join("user.rs"), &user_rs).unwrap();
+    fs::write(src_dir.join("tests.rs"), &tests_rs).unwrap();
     fs::write(src_dir.join("lib.rs"), LIB_RS).unwrap();
-    fs::write(crate_dir.join("Cargo.toml"), cargo_toml(fixture_name)).unwrap();
+    fs::write(crate_dir.join("Cargo.toml"), cargo_toml(crate_name)).unwrap();
+    crate_dir
+}
 
-    let target_dir = workspace_target.join("sillyecs-compile-fixtures-target");
+/// Runs the cargo `subcommand` (e.g. `test` or `check`) against a rendered fixture crate.
+fn run_cargo(crate_dir: &Path, subcommand: &str) -> Output {
+    let target_dir = workspace_target_dir().join("sillyecs-compile-fixtures-target");
 
-    let output = Command::new(env!("CARGO"))
-        .arg("check")
+    Command::new(env!("CARGO"))
+        .arg(subcommand)
         .arg("--quiet")
         .arg("--manifest-path")
         .arg(crate_dir.join("Cargo.toml"))
@@ -87,18 +342,7 @@ fn run_fixture(fixture_name: &str) {
         // Inherit RUSTFLAGS / RUSTC etc. from the parent so the fixture builds
         // with the same toolchain the test runner is using.
         .output()
-        .expect("spawn cargo check");
-
-    if !output.status.success() {
-        panic!(
-            "generated code from fixture `{fixture_name}` failed to compile.\n\
-             crate at: {}\n\
-             --- stdout ---\n{}\n--- stderr ---\n{}",
-            crate_dir.display(),
-            String::from_utf8_lossy(&output.stdout),
-            String::from_utf8_lossy(&output.stderr),
-        );
-    }
+        .unwrap_or_else(|e| panic!("spawn cargo {subcommand}: {e}"))
 }
 
 fn workspace_target_dir() -> PathBuf {
@@ -127,7 +371,7 @@ publish = false
 path = "src/lib.rs"
 
 [dependencies]
-sillyecs = {{ path = "{path}" }}
+sillyecs = {{ path = "{path}", features = ["world-tag"] }}
 tracing = "0.1"
 rayon = "1"
 
@@ -138,6 +382,13 @@ rayon = "1"
     )
 }
 
+/// The `include!`s of the separately generated files in [`LIB_RS`].
+const SEPARATE_INCLUDES: &str = r#"include!("generated/components_gen.rs");
+include!("generated/archetypes_gen.rs");
+include!("generated/systems_gen.rs");
+include!("generated/world_gen.rs");
+"#;
+
 const LIB_RS: &str = r#"//! Auto-generated fixture crate. See compile_generated.rs in sillyecs-build.
 #![allow(dead_code)]
 #![allow(unused_imports)]
@@ -148,4 +399,10 @@ include!("generated/archetypes_gen.rs");
 include!("generated/systems_gen.rs");
 include!("generated/world_gen.rs");
 include!("user.rs");
+
+#[cfg(test)]
+mod fixture_tests {
+    use super::*;
+    include!("tests.rs");
+}
 "#;
@@ -0,0 +1,102 @@
+// Runtime assertions for the `command_queues` fixture. Included into the
+// generated fixture crate as a `#[cfg(test)]` module.
+
+fn spawn_marker(x: f32) -> WorldCommand<()> {
+    WorldCommand::SpawnEntity(ArchetypeEntityData::Marker(MarkerEntityData {
+        position: PositionData { x },
+    }))
+}
+
+fn structural_marker(x: f32) -> StructuralQueue<()> {
+    StructuralQueue::SpawnEntity(ArchetypeEntityData::Marker(MarkerEntityData {
+        position: PositionData { x },
+    }))
+}
+
+fn gameplay_marker(x: f32) -> GameplayQueue<()> {
+    GameplayQueue::SpawnEntity(ArchetypeEntityData::Marker(MarkerEntityData {
+        position: PositionData { x },
+    }))
+}
+
+fn new_world() -> MainWorld<NoOpPhaseEvents, CommandQueue> {
+    MainWorld::new(
+        &SystemFactory,
+        CommandQueue::default(),
+        CommandQueue::default(),
+        CommandQueue::default(),
+    )
+}
+
+#[test]
+fn phase_queues_drain_per_phase_and_frame_queues_at_the_end_of_the_frame() {
+    let mut world = new_world();
+    world.command_to(structural_marker(1.0)).unwrap();
+    world.command_to(gameplay_marker(2.0)).unwrap();
+
+    let plan = world.begin_frame(0.016);
+    assert_eq!(world.len(), 1, "the structural queue drains when the frame begins");
+
+    for batch in plan {
+        world.run_frame_batch(batch);
+    }
+    assert_eq!(world.len(), 1, "the gameplay queue waits for the end of the frame");
+
+    world.end_frame();
+    assert_eq!(world.len(), 2);
+}
+
+#[test]
+fn stepping_a_phase_leaves_frame_queues_alone() {
+    let mut world = new_world();
+    world.command_to(spawn_marker(1.0)).unwrap();
+    world.command_to(gameplay_marker(2.0)).unwrap();
+
+    world.step_phase(SystemPhase::Update, &world.context.clone());
+    assert_eq!(world.len(), 1);
+
+    world.apply_command_queue();
+    assert_eq!(world.len(), 2, "applying the command queue drains every queue");
+}
+
+#[test]
+fn applying_all_phases_drains_every_queue() {
+    let mut world = MainWorld::<NoOpPhaseEvents, CommandQueue>::default();
+    world.command(spawn_marker(1.0)).unwrap();
+    world.structural_queue().send(spawn_marker(2.0)).unwrap();
+    world.gameplay_queue().send(spawn_marker(3.0)).unwrap();
+
+    world.apply_system_phases();
+    assert_eq!(world.len(), 3);
+}
+
+#[test]
+fn deferred_spawns_wait_for_maintenance() {
+    let mut world = new_world();
+    world.command(spawn_marker(-1.0)).unwrap();
+    world.apply_command_queue();
+    assert_eq!(world.len(), 1);
+
+    world.step_phase(SystemPhase::Update, &world.context.clone());
+    assert_eq!(world.len(), 1, "spawns deferred while iterating wait for maintenance");
+
+    world.maintain();
+    assert_eq!(world.len(), 2);
+
+    world.apply_system_phases();
+    assert_eq!(world.len(), 3, "maintenance runs at the end of the frame");
+}
+
+#[test]
+fn duplicate_deferred_despawns_are_skipped() {
+    let mut world = new_world();
+    world.command(spawn_marker(100.0)).unwrap();
+    world.apply_command_queue();
+    assert_eq!(world.len(), 1);
+
+    world.step_phase(SystemPhase::Update, &world.context.clone());
+    assert_eq!(world.len(), 1, "despawns deferred while iterating wait for maintenance");
+
+    world.maintain();
+    assert_eq!(world.len(), 0, "the second despawn of the same entity is a no-op");
+}
@@ -0,0 +1,138 @@
+// Hand-written user-side stubs for the `command_queues` compile fixture. Pairs
+// with `ecs.yaml` in this directory; included from the synthetic library crate
+// built by `tests/compile_generated.rs`.
+
+use std::collections::{HashMap, VecDeque};
+use std::convert::Infallible;
+use std::sync::Mutex;
+
+pub type EntityLocationMap<K, V> = HashMap<K, V>;
+
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct PositionData {
+    pub x: f32,
+}
+
+/// Spawns a mirrored marker at `-x` for every marker at a negative `x`, deferred to the
+/// maintenance pass of the world.
+#[derive(Debug, Default)]
+pub struct MirrorSystemData;
+
+impl Default for MirrorSystem {
+    fn default() -> Self {
+        Self(MirrorSystemData)
+    }
+}
+
+impl ApplyMirrorSystem for MirrorSystem {
+    type Error = Infallible;
+
+    fn apply_single(&mut self, position: &PositionComponent, commands: &impl WorldCommandSender) {
+        if position.x < 0.0 {
+            let mirrored = ArchetypeEntityData::Marker(MarkerEntityData {
+                position: PositionData { x: -position.x },
+            });
+            commands
+                .send(WorldCommand::Deferred(DeferredCommand::SpawnEntity(mirrored)))
+                .expect("the command queue accepts commands");
+        }
+    }
+}
+
+/// Despawns every marker at an `x` of 100 or more, deferred to the maintenance pass of the world.
+/// Requests each despawn twice, like two systems culling the same entity would.
+#[derive(Debug, Default)]
+pub struct CullSystemData;
+
+impl Default for CullSystem {
+    fn default() -> Self {
+        Self(CullSystemData)
+    }
+}
+
+impl ApplyCullSystem for CullSystem {
+    type Error = Infallible;
+
+    fn apply_single(
+        &mut self,
+        entity: ::sillyecs::EntityId,
+        position: &PositionComponent,
+        commands: &impl WorldCommandSender,
+    ) {
+        if position.x >= 100.0 {
+            for _ in 0..2 {
+                commands
+                    .send(WorldCommand::Deferred(DeferredCommand::DespawnEntity(entity)))
+                    .expect("the command queue accepts commands");
+            }
+        }
+    }
+}
+
+pub struct SystemFactory;
+
+impl CreateSystem<MirrorSystem> for SystemFactory {
+    fn create(&self) -> MirrorSystem {
+        MirrorSystem::default()
+    }
+}
+
+impl CreateSystem<CullSystem> for SystemFactory {
+    fn create(&self) -> CullSystem {
+        CullSystem::default()
+    }
+}
+
+const _: () = assert_all_systems_implemented::<SystemFactory>();
+
+/// A queue of world commands; the world holds one instance per command queue.
+#[derive(Default)]
+pub struct CommandQueue {
+    queue: Mutex<VecDeque<WorldCommand<()>>>,
+}
+
+#[derive(Debug)]
+pub struct CommandQueueClosed;
+
+impl std::fmt::Display for CommandQueueClosed {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("command queue mutex poisoned")
+    }
+}
+
+impl std::error::Error for CommandQueueClosed {}
+
+impl WorldUserCommand for CommandQueue {
+    type UserCommand = ();
+}
+
+impl WorldCommandSender for CommandQueue {
+    type Error = CommandQueueClosed;
+
+    fn send(&self, command: WorldCommand<Self::UserCommand>) -> Result<(), Self::Error> {
+        self.queue
+            .lock()
+            .map_err(|_| CommandQueueClosed)?
+            .push_back(command);
+        Ok(())
+    }
+}
+
+impl WorldCommandReceiver for CommandQueue {
+    type Error = CommandQueueClosed;
+
+    fn recv(&self) -> Result<Option<WorldCommand<Self::UserCommand>>, Self::Error> {
+        Ok(self
+            .queue
+            .lock()
+            .map_err(|_| CommandQueueClosed)?
+            .pop_front())
+    }
+}
+
+impl<E, Q> WorldUserCommandHandler for MainWorld<E, Q>
+where
+    Q: WorldUserCommand<UserCommand = ()>,
+{
+    fn handle_user_command(&mut self, _command: Self::UserCommand) {}
+}
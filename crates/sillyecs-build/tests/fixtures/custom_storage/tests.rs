@@ -0,0 +1,76 @@
+// Runtime assertions for the `custom_storage` fixture. Included into the
+// generated fixture crate as a `#[cfg(test)]` module.
+
+fn new_world() -> MainWorld<NoOpPhaseEvents, NoCommands> {
+    MainWorld::new(&SystemFactory, NoCommands)
+}
+
+fn spawn_particle(world: &mut MainWorld<NoOpPhaseEvents, NoCommands>, x: f32, speed: f32) {
+    let _ = world.spawn_particle(ParticleEntityComponents {
+        position: PositionComponent::new(PositionData { x }),
+        velocity: VelocityComponent::new(VelocityData { x: speed }),
+    });
+}
+
+#[test]
+fn systems_run_over_custom_storage() {
+    let mut world = new_world();
+    spawn_particle(&mut world, 0.0, 1.0);
+    spawn_particle(&mut world, 10.0, -2.0);
+
+    let particles = &world.archetypes.collection.particle;
+    assert_eq!(particles.positions.pushes, 2);
+    assert_eq!(particles.velocities.pushes, 2);
+
+    world.apply_system_phases();
+    world.par_apply_system_phases();
+
+    let positions: Vec<f32> = world
+        .archetypes
+        .collection
+        .particle
+        .positions
+        .iter()
+        .map(|position| position.x)
+        .collect();
+    assert_eq!(positions, [2.0, 6.0]);
+}
+
+#[test]
+fn despawn_and_capacity_go_through_custom_storage() {
+    let mut world = new_world();
+    spawn_particle(&mut world, 0.0, 0.0);
+    let second = world.archetypes.collection.particle.entities.len();
+    spawn_particle(&mut world, 1.0, 0.0);
+    let id = world.archetypes.collection.particle.entities[second];
+
+    world.despawn_by_id(id).expect("entity was spawned");
+    let particles = &world.archetypes.collection.particle;
+    assert_eq!(particles.len(), 1);
+    assert_eq!(particles.positions.len(), 1);
+    assert_eq!(particles.positions[0].x, 0.0);
+
+    world.archetypes.collection.particle.reserve(16);
+    let particles = &world.archetypes.collection.particle;
+    assert!(::sillyecs::ComponentStorage::capacity(&particles.positions) >= 17);
+}
+
+#[test]
+#[allow(deprecated)]
+fn promotion_takes_custom_storage() {
+    let mut particles = ParticleArchetype::default();
+    ::sillyecs::ComponentStorage::push(
+        &mut particles.positions,
+        PositionComponent::new(PositionData { x: 1.0 }),
+    );
+    ::sillyecs::ComponentStorage::push(
+        &mut particles.velocities,
+        VelocityComponent::new(VelocityData { x: 2.0 }),
+    );
+
+    let mut masses = TrackedVec::default();
+    ::sillyecs::ComponentStorage::push(&mut masses, MassComponent::new(MassData(3.0)));
+    let heavy = particles.promote_to_heavy_particles(masses);
+    assert_eq!(heavy.positions.pushes, 1);
+    assert_eq!(heavy.masses.len(), 1);
+}
@@ -0,0 +1,142 @@
+// Hand-written user-side stubs for the `custom_storage` compile fixture. Pairs
+// with `ecs.yaml` in this directory; included from the synthetic library crate
+// built by `tests/compile_generated.rs`.
+
+use std::collections::HashMap;
+use std::convert::Infallible;
+use std::ops::{Deref, DerefMut};
+
+pub type EntityLocationMap<K, V> = HashMap<K, V>;
+
+// --- Storage backend ----------------------------------------------------------
+//
+// A trivial alternative to `Vec` that counts the values pushed into it, so the
+// tests can tell that the generated archetypes actually go through it.
+
+#[derive(Debug, Clone)]
+pub struct TrackedVec<T> {
+    values: Vec<T>,
+    pub pushes: usize,
+}
+
+impl<T> Default for TrackedVec<T> {
+    fn default() -> Self {
+        Self {
+            values: Vec::new(),
+            pushes: 0,
+        }
+    }
+}
+
+impl<T> Deref for TrackedVec<T> {
+    type Target = [T];
+
+    fn deref(&self) -> &[T] {
+        &self.values
+    }
+}
+
+impl<T> DerefMut for TrackedVec<T> {
+    fn deref_mut(&mut self) -> &mut [T] {
+        &mut self.values
+    }
+}
+
+impl<T> ::sillyecs::ComponentStorage<T> for TrackedVec<T> {
+    fn push(&mut self, value: T) {
+        self.pushes += 1;
+        self.values.push(value);
+    }
+
+    fn swap_remove(&mut self, index: usize) -> T {
+        self.values.swap_remove(index)
+    }
+
+    fn reserve(&mut self, additional: usize) {
+        self.values.reserve(additional);
+    }
+
+    fn shrink_to_fit(&mut self) {
+        self.values.shrink_to_fit();
+    }
+
+    fn capacity(&self) -> usize {
+        self.values.capacity()
+    }
+}
+
+// --- Component data structs ----------------------------------------------------
+
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct PositionData {
+    pub x: f32,
+}
+
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct VelocityData {
+    pub x: f32,
+}
+
+#[derive(Debug, Default, Clone)]
+pub struct MassData(pub f32);
+
+// --- Systems ------------------------------------------------------------------
+
+#[derive(Debug, Default)]
+pub struct DriftSystemData;
+
+impl Default for DriftSystem {
+    fn default() -> Self {
+        Self(DriftSystemData)
+    }
+}
+
+pub struct SystemFactory;
+
+impl CreateSystem<DriftSystem> for SystemFactory {
+    fn create(&self) -> DriftSystem {
+        DriftSystem::default()
+    }
+}
+
+impl ApplyDriftSystem for DriftSystem {
+    type Error = Infallible;
+
+    fn apply_single(&mut self, velocity: &VelocityComponent, position: &mut PositionComponent) {
+        position.x += velocity.x;
+    }
+}
+
+const _: () = assert_all_systems_implemented::<SystemFactory>();
+
+// --- Command queue ------------------------------------------------------------
+
+#[derive(Debug, Default)]
+pub struct NoCommands;
+
+impl WorldUserCommand for NoCommands {
+    type UserCommand = ();
+}
+
+impl WorldCommandSender for NoCommands {
+    type Error = Infallible;
+
+    fn send(&self, _command: WorldCommand<Self::UserCommand>) -> Result<(), Self::Error> {
+        Ok(())
+    }
+}
+
+impl WorldCommandReceiver for NoCommands {
+    type Error = Infallible;
+
+    fn recv(&self) -> Result<Option<WorldCommand<Self::UserCommand>>, Self::Error> {
+        Ok(None)
+    }
+}
+
+impl<E, Q> WorldUserCommandHandler for MainWorld<E, Q>
+where
+    Q: WorldUserCommand<UserCommand = ()>,
+{
+    fn handle_user_command(&mut self, _command: Self::UserCommand) {}
+}
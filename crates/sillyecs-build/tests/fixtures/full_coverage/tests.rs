@@ -0,0 +1,1406 @@
+// Runtime assertions against the generated code for the `full_coverage`
+// fixture. Included as a `#[cfg(test)]` module from the synthetic library
+// crate built by `tests/compile_generated.rs`.
+
+#[test]
+fn component_registry_lists_all_components() {
+    assert_eq!(
+        COMPONENTS,
+        &[("Position", 1), ("Velocity", 2), ("Health", 3), ("Sprite", 4)]
+    );
+}
+
+#[test]
+fn component_id_by_name_finds_known_component() {
+    assert_eq!(component_id_by_name("Health"), Some(ComponentId::Health));
+    assert_eq!(
+        component_id_by_name("Position").map(|id| id as u32),
+        Some(1)
+    );
+}
+
+#[test]
+fn component_id_by_name_rejects_unknown_component() {
+    assert_eq!(component_id_by_name("Mass"), None);
+    assert_eq!(component_id_by_name("HealthComponent"), None);
+}
+
+#[test]
+fn system_phases_match_schema() {
+    let names: Vec<_> = SystemPhase::ALL.iter().map(SystemPhase::name).collect();
+    assert_eq!(names, ["Boot", "FixedUpdate", "Update", "Render", "Forces"]);
+    assert_eq!(SystemPhase::ALL.len(), SystemPhase::COUNT);
+}
+
+#[test]
+fn systems_in_phase_matches_schema() {
+    let world: MainWorld<NoOpPhaseEvents, CommandQueue> =
+        MainWorld::new(&SystemFactory, MainWorldStates::default(), CommandQueue::new());
+    assert_eq!(world.systems_in_phase(SystemPhase::Boot), &[SystemId::Attract]);
+    assert_eq!(
+        world.systems_in_phase(SystemPhase::FixedUpdate),
+        &[SystemId::Step, SystemId::Tally]
+    );
+    assert_eq!(
+        world.systems_in_phase(SystemPhase::Update),
+        &[SystemId::Bounce, SystemId::Heal]
+    );
+    assert_eq!(world.systems_in_phase(SystemPhase::Render), &[SystemId::Draw]);
+    assert_eq!(
+        world.systems_in_phase(SystemPhase::Forces),
+        &[SystemId::Gravity, SystemId::Wind, SystemId::Damp]
+    );
+}
+
+#[test]
+fn default_world_starts_empty() {
+    let world: MainWorld<NoOpPhaseEvents, CommandQueue> = MainWorld::default();
+    assert!(world.is_empty());
+    assert_eq!(world.systems.as_step_ref().runs, 0);
+
+    let world: MainWorld<NoOpPhaseEvents, CommandQueue> =
+        MainWorld::new(&SystemFactory, MainWorldStates::default(), CommandQueue::new());
+    assert!(world.is_empty());
+}
+
+#[test]
+fn lookup_mut_reaches_other_archetypes_only() {
+    let mut world: MainWorld<NoOpPhaseEvents, CommandQueue> =
+        MainWorld::new(&SystemFactory, MainWorldStates::default(), CommandQueue::new());
+    let decoration = world.spawn_decoration(DecorationEntityComponents {
+        position: PositionComponent::new(PositionData { x: 10.0, y: 0.0 }),
+        sprite: SpriteComponent::new(SpriteData(0)),
+    });
+    let living = world.spawn_living_particle(LivingParticleEntityComponents {
+        position: PositionComponent::new(PositionData::default()),
+        velocity: VelocityComponent::new(VelocityData::default()),
+        health: HealthComponent::new(HealthData(1)),
+    });
+
+    let attract = world.systems.as_attract_mut();
+    attract.target = Some(decoration);
+    attract.probe = Some(living);
+    world.apply_system_phase_boot();
+
+    assert_eq!(ComponentAccess::get_position_component(&world, decoration).unwrap().x, 11.0);
+    assert_eq!(ComponentAccess::get_position_component(&world, living).unwrap().x, 11.0);
+    assert!(!world.systems.as_attract_ref().probe_reachable);
+}
+
+#[test]
+fn world_into_iterator_yields_all_entity_ids() {
+    let mut world: MainWorld<NoOpPhaseEvents, CommandQueue> =
+        MainWorld::new(&SystemFactory, MainWorldStates::default(), CommandQueue::new());
+    let mut spawned = vec![
+        world.spawn_particle(ParticleEntityComponents {
+            position: PositionComponent::new(PositionData::default()),
+            velocity: VelocityComponent::new(VelocityData::default()),
+        }),
+        world.spawn_decoration(DecorationEntityComponents {
+            position: PositionComponent::new(PositionData::default()),
+            sprite: SpriteComponent::new(SpriteData(0)),
+        }),
+        world.spawn_particle(ParticleEntityComponents {
+            position: PositionComponent::new(PositionData::default()),
+            velocity: VelocityComponent::new(VelocityData::default()),
+        }),
+    ];
+
+    let mut ids = Vec::new();
+    for id in &world {
+        ids.push(id);
+    }
+
+    assert_eq!(ids.len(), world.len());
+    ids.sort();
+    spawned.sort();
+    assert_eq!(ids, spawned);
+}
+
+#[test]
+fn memory_usage_follows_capacity() {
+    let mut world: MainWorld<NoOpPhaseEvents, CommandQueue> =
+        MainWorld::new(&SystemFactory, MainWorldStates::default(), CommandQueue::new());
+    let empty = world.memory_usage();
+
+    for _ in 0..100 {
+        let _ = world.spawn_particle(ParticleEntityComponents {
+            position: PositionComponent::new(PositionData::default()),
+            velocity: VelocityComponent::new(VelocityData::default()),
+        });
+    }
+    let spawned = world.memory_usage();
+    assert!(spawned > empty);
+
+    world.reserve_particle(1000);
+    let reserved = world.memory_usage();
+    assert!(reserved > spawned);
+
+    world.shrink_to_fit();
+    let shrunk = world.memory_usage();
+    assert!(shrunk < reserved);
+    assert!(shrunk >= 100 * core::mem::size_of::<PositionComponent>());
+}
+
+#[test]
+fn reserve_and_shrink_to_fit_adjust_capacity() {
+    let mut world: MainWorld<NoOpPhaseEvents, CommandQueue> =
+        MainWorld::new(&SystemFactory, MainWorldStates::default(), CommandQueue::new());
+
+    world.reserve_particle(64);
+    let reserved = world.archetypes.collection.particle.capacity();
+    assert!(reserved >= 64);
+
+    let position_ptr = world.archetypes.collection.particle.positions.as_ptr();
+    for _ in 0..64 {
+        let _ = world.spawn_particle(ParticleEntityComponents {
+            position: PositionComponent::new(PositionData::default()),
+            velocity: VelocityComponent::new(VelocityData::default()),
+        });
+    }
+    assert_eq!(world.archetypes.collection.particle.capacity(), reserved);
+    assert_eq!(
+        world.archetypes.collection.particle.positions.as_ptr(),
+        position_ptr,
+        "spawning within the reserved capacity must not reallocate"
+    );
+
+    let ids: Vec<_> = (&world).into_iter().skip(4).collect();
+    for id in ids {
+        world.despawn_by_id(id).expect("entity was spawned");
+    }
+    world.shrink_to_fit();
+    assert_eq!(world.len(), 4);
+    assert!(world.archetypes.collection.particle.capacity() < reserved);
+}
+
+#[test]
+fn extend_reserves_and_spawns_every_entity() {
+    let mut world: MainWorld<NoOpPhaseEvents, CommandQueue> =
+        MainWorld::new(&SystemFactory, MainWorldStates::default(), CommandQueue::new());
+
+    let components: Vec<_> = (0..48)
+        .map(|i| ParticleEntityComponents {
+            position: PositionComponent::new(PositionData { x: i as f32, y: 0.0 }),
+            velocity: VelocityComponent::new(VelocityData::default()),
+        })
+        .collect();
+    world.extend(components);
+    assert_eq!(world.archetypes.collection.particle.len(), 48);
+    assert!(world.archetypes.collection.particle.capacity() >= 48);
+    assert_eq!(world.total_spawned_particles(), 48);
+
+    world.extend((0..16).map(|i| ParticleEntityData {
+        position: PositionData { x: -(i as f32), y: 0.0 },
+        velocity: VelocityData::default(),
+    }));
+    assert_eq!(world.archetypes.collection.particle.len(), 64);
+    assert_eq!(world.len(), 64);
+}
+
+#[test]
+fn drain_particle_yields_spawned_components_and_empties_archetype() {
+    let mut world: MainWorld<NoOpPhaseEvents, CommandQueue> =
+        MainWorld::new(&SystemFactory, MainWorldStates::default(), CommandQueue::new());
+
+    let spawned: Vec<_> = (0..3)
+        .map(|i| {
+            let components = ParticleEntityComponents {
+                position: PositionComponent::new(PositionData { x: i as f32, y: 0.0 }),
+                velocity: VelocityComponent::new(VelocityData { x: 0.0, y: i as f32 }),
+            };
+            (world.spawn_particle(components.clone()), components)
+        })
+        .collect();
+    let decoration = world.spawn_decoration(DecorationEntityComponents {
+        position: PositionComponent::new(PositionData::default()),
+        sprite: SpriteComponent::new(SpriteData(1)),
+    });
+
+    let drained: Vec<_> = world.drain_particle().collect();
+    assert_eq!(drained, spawned);
+    assert!(world.archetypes.collection.particle.is_empty());
+    assert_eq!(world.len(), 1);
+    assert_eq!(world.total_despawned_particles(), 3);
+    for (id, _) in &drained {
+        assert!(ComponentAccess::get_position_component(&world, *id).is_none());
+        assert!(world.despawn_by_id(*id).is_err());
+    }
+    assert!(ComponentAccess::get_position_component(&world, decoration).is_some());
+}
+
+#[test]
+fn systems_skip_iteration_without_matching_entities() {
+    let mut world: MainWorld<NoOpPhaseEvents, CommandQueue> =
+        MainWorld::new(&SystemFactory, MainWorldStates::default(), CommandQueue::new());
+
+    world.apply_system_phase_render();
+    world.par_apply_system_phase_render();
+    assert_eq!(world.systems.draw.batches, 0);
+
+    let _ = world.spawn_decoration(DecorationEntityComponents {
+        position: PositionComponent::new(PositionData::default()),
+        sprite: SpriteComponent::new(SpriteData(1)),
+    });
+    world.apply_system_phase_render();
+    world.par_apply_system_phase_render();
+    assert_eq!(world.systems.draw.batches, 2);
+}
+
+#[test]
+fn velocities_mut_where_health_only_touches_living_particles() {
+    let mut world: MainWorld<NoOpPhaseEvents, CommandQueue> =
+        MainWorld::new(&SystemFactory, MainWorldStates::default(), CommandQueue::new());
+
+    let particle = world.spawn_particle(ParticleEntityComponents {
+        position: PositionComponent::new(PositionData::default()),
+        velocity: VelocityComponent::new(VelocityData { x: 1.0, y: 1.0 }),
+    });
+    let living = world.spawn_living_particle(LivingParticleEntityComponents {
+        position: PositionComponent::new(PositionData::default()),
+        velocity: VelocityComponent::new(VelocityData { x: 1.0, y: 1.0 }),
+        health: HealthComponent::new(HealthData(1)),
+    });
+
+    let mut touched = 0;
+    for velocity in world.velocities_mut_where::<HealthComponent>() {
+        velocity.x = 0.0;
+        touched += 1;
+    }
+    assert_eq!(touched, 1);
+    assert_eq!(ComponentAccess::get_velocity_component(&world, living).unwrap().x, 0.0);
+    assert_eq!(ComponentAccess::get_velocity_component(&world, particle).unwrap().x, 1.0);
+    assert_eq!(world.positions_mut_where::<SpriteComponent>().count(), 0);
+}
+
+#[test]
+fn with_capacity_preallocates_every_archetype() {
+    let capacities = MainWorldCapacities {
+        particles: 100,
+        living_particles: 20,
+        decorations: 3,
+    };
+    assert_eq!(capacities.total(), 123);
+
+    let world: MainWorld<NoOpPhaseEvents, CommandQueue> = MainWorld::with_capacity(
+        &SystemFactory,
+        MainWorldStates::default(),
+        CommandQueue::new(),
+        capacities,
+    );
+
+    let collection = &world.archetypes.collection;
+    assert!(collection.particle.capacity() >= 100);
+    assert!(collection.particle.positions.capacity() >= 100);
+    assert!(collection.particle.velocities.capacity() >= 100);
+    assert!(collection.living_particle.capacity() >= 20);
+    assert!(collection.living_particle.healths.capacity() >= 20);
+    assert!(collection.decoration.capacity() >= 3);
+    assert!(collection.decoration.sprites.capacity() >= 3);
+    assert!(world.archetypes.entity_locations.capacity() >= 123);
+    assert!(world.is_empty());
+}
+
+#[test]
+fn despawning_last_entity_of_archetype_does_not_panic() {
+    let mut world: MainWorld<NoOpPhaseEvents, CommandQueue> =
+        MainWorld::new(&SystemFactory, MainWorldStates::default(), CommandQueue::new());
+    let spawn = |world: &mut MainWorld<NoOpPhaseEvents, CommandQueue>, x: f32| {
+        world.spawn_particle(ParticleEntityComponents {
+            position: PositionComponent::new(PositionData { x, y: 0.0 }),
+            velocity: VelocityComponent::new(VelocityData::default()),
+        })
+    };
+    let first = spawn(&mut world, 1.0);
+    let last = spawn(&mut world, 2.0);
+
+    world.despawn_by_id(last).expect("entity was spawned");
+    assert_eq!(world.len(), 1);
+    assert_eq!(ComponentAccess::get_position_component(&world, first).unwrap().x, 1.0);
+    assert!(world.despawn_by_id(last).is_err());
+}
+
+// Runs under Miri (`cargo +nightly miri test` in the fixture crate) to check that the two
+// mutable borrows per item never alias.
+#[test]
+fn two_output_items_do_not_alias() {
+    let mut positions: Vec<_> = (0..4)
+        .map(|i| PositionComponent::new(PositionData { x: i as f32, y: -1.0 }))
+        .collect();
+    let mut velocities: Vec<_> = (0..4)
+        .map(|i| VelocityComponent::new(VelocityData { x: i as f32, y: 2.0 }))
+        .collect();
+
+    let entities: Vec<_> = (0..4).map(|_| ::sillyecs::EntityId::new()).collect();
+
+    let mut items: Vec<BounceSystemItem<'_>> =
+        BounceSystem::iter_items(&entities, &mut positions, &mut velocities).collect();
+    assert_eq!(items.len(), 4);
+    assert!(items.iter().zip(&entities).all(|(item, &id)| item.entity == id));
+    for item in &mut items {
+        item.position.y = item.velocity.x;
+        item.velocity.y = item.position.x;
+    }
+    drop(items);
+
+    for (i, (position, velocity)) in positions.iter().zip(&velocities).enumerate() {
+        assert_eq!(position.y, i as f32);
+        assert_eq!(velocity.y, i as f32);
+    }
+}
+
+#[test]
+fn two_output_system_runs_through_world() {
+    let mut world: MainWorld<NoOpPhaseEvents, CommandQueue> =
+        MainWorld::new(&SystemFactory, MainWorldStates::default(), CommandQueue::new());
+    let id = world.spawn_particle(ParticleEntityComponents {
+        position: PositionComponent::new(PositionData { x: 0.0, y: -3.0 }),
+        velocity: VelocityComponent::new(VelocityData { x: 0.0, y: -1.0 }),
+    });
+    world.request_update_phase();
+    world.par_apply_system_phases();
+
+    assert_eq!(ComponentAccess::get_position_component(&world, id).unwrap().y, 3.0);
+    assert_eq!(ComponentAccess::get_velocity_component(&world, id).unwrap().y, 1.0);
+}
+
+#[test]
+fn component_versions_detect_migrations() {
+    assert_eq!(HealthComponent::VERSION, 2);
+    assert_eq!(PositionComponent::VERSION, 0);
+    assert_eq!(ComponentId::Health.version(), 2);
+
+    // Saved data recorded with the current schema needs no migration.
+    let saved: Vec<(String, u32)> = COMPONENT_VERSIONS
+        .iter()
+        .map(|&(name, version)| (name.to_string(), version))
+        .collect();
+    assert!(check_component_versions(saved.iter().map(|(n, v)| (n.as_str(), *v))).is_empty());
+
+    // Saved data from an older schema surfaces each changed or removed component.
+    let old = [("Position", 0), ("Health", 1), ("Mana", 3)];
+    assert_eq!(
+        check_component_versions(old),
+        vec![
+            ComponentVersionMismatch::Changed {
+                id: ComponentId::Health,
+                saved: 1,
+                current: 2,
+            },
+            ComponentVersionMismatch::Unknown {
+                name: "Mana".to_string(),
+                saved: 3,
+            },
+        ]
+    );
+}
+
+#[test]
+fn ignore_fixed_system_runs_once_per_frame() {
+    let mut world: MainWorld<NoOpPhaseEvents, CommandQueue> =
+        MainWorld::new(&SystemFactory, MainWorldStates::default(), CommandQueue::new());
+
+    // The first frame has a zero delta; preload three and a half fixed steps.
+    world.fixed_accumulators.fixed_update = 3.5 * SystemPhase::FIXED_UPDATE_SECS;
+    world.apply_system_phases();
+    assert_eq!(world.systems.as_step_ref().runs, 3);
+    assert_eq!(world.systems.as_tally_ref().runs, 1);
+
+    // No fixed step is due in this frame, but the variable-rate system still runs once.
+    world.fixed_accumulators.fixed_update = -1.0;
+    world.par_apply_system_phases();
+    assert_eq!(world.systems.as_step_ref().runs, 3);
+    assert_eq!(world.systems.as_tally_ref().runs, 2);
+}
+
+#[test]
+fn spawn_handle_allows_immediate_mutation() {
+    let mut world: MainWorld<NoOpPhaseEvents, CommandQueue> =
+        MainWorld::new(&SystemFactory, MainWorldStates::default(), CommandQueue::new());
+    let _ = world.spawn_particle(ParticleEntityComponents {
+        position: PositionComponent::new(PositionData::default()),
+        velocity: VelocityComponent::new(VelocityData::default()),
+    });
+
+    let handle = world.spawn_particle_handle(ParticleEntityComponents {
+        position: PositionComponent::new(PositionData::default()),
+        velocity: VelocityComponent::new(VelocityData { x: 1.0, y: 0.0 }),
+    });
+    handle.position.x = 3.0;
+    let id = handle.entity_id;
+
+    let position = ComponentAccess::get_position_component(&world, id).unwrap();
+    assert_eq!(position.x, 3.0);
+    let velocity = ComponentAccess::get_velocity_component(&world, id).unwrap();
+    assert_eq!(velocity.x, 1.0);
+}
+
+#[test]
+fn observer_receives_spawn_and_despawn() {
+    use std::sync::{Arc, Mutex};
+
+    #[derive(Default)]
+    struct Recorder(Arc<Mutex<Vec<(&'static str, ::sillyecs::EntityId, ArchetypeId)>>>);
+
+    impl WorldObserver for Recorder {
+        fn on_spawn(&mut self, id: ::sillyecs::EntityId, archetype: ArchetypeId) {
+            self.0.lock().unwrap().push(("spawn", id, archetype));
+        }
+
+        fn on_despawn(&mut self, id: ::sillyecs::EntityId, archetype: ArchetypeId) {
+            self.0.lock().unwrap().push(("despawn", id, archetype));
+        }
+    }
+
+    let mut world: MainWorld<NoOpPhaseEvents, CommandQueue> =
+        MainWorld::new(&SystemFactory, MainWorldStates::default(), CommandQueue::new());
+    let recorder = Recorder::default();
+    let events = Arc::clone(&recorder.0);
+    assert!(world.set_observer(Box::new(recorder)).is_none());
+
+    let particle = world.spawn_particle(ParticleEntityComponents {
+        position: PositionComponent::new(PositionData::default()),
+        velocity: VelocityComponent::new(VelocityData::default()),
+    });
+    let decoration = world.spawn_decoration_with(
+        PositionComponent::new(PositionData::default()),
+        SpriteComponent::new(SpriteData(7)),
+    );
+    world.despawn_by_id(particle).unwrap();
+
+    assert_eq!(
+        *events.lock().unwrap(),
+        [
+            ("spawn", particle, ArchetypeId::Particle),
+            ("spawn", decoration, ArchetypeId::Decoration),
+            ("despawn", particle, ArchetypeId::Particle),
+        ]
+    );
+
+    // Without an observer, structural changes go unreported.
+    assert!(world.take_observer().is_some());
+    world.despawn_by_id(decoration).unwrap();
+    assert_eq!(events.lock().unwrap().len(), 3);
+}
+
+#[test]
+fn world_is_empty_until_spawn() {
+    let mut world: MainWorld<NoOpPhaseEvents, CommandQueue> =
+        MainWorld::new(&SystemFactory, MainWorldStates::default(), CommandQueue::new());
+    assert!(world.is_empty());
+
+    let id = world.spawn_decoration_with(
+        PositionComponent::new(PositionData::default()),
+        SpriteComponent::new(SpriteData(1)),
+    );
+    assert!(!world.is_empty());
+
+    world.despawn_by_id(id).unwrap();
+    assert!(world.is_empty());
+}
+
+#[test]
+fn phase_has_systems_matches_schema() {
+    type World = MainWorld<NoOpPhaseEvents, CommandQueue>;
+    const RENDER_HAS_SYSTEMS: bool = World::phase_has_systems(SystemPhase::Render);
+    assert!(RENDER_HAS_SYSTEMS);
+    for phase in SystemPhase::ALL {
+        assert!(World::phase_has_systems(phase), "{}", phase.name());
+    }
+}
+
+// The frame context is only part of a system's hook signatures if the system sets `context: true`.
+#[test]
+fn frame_context_parameter_only_for_context_systems() {
+    let bounce: fn(&BounceSystem) -> bool = <BounceSystem as ApplyBounceSystem>::is_ready;
+    let tally: fn(&TallySystem, &::sillyecs::FrameContext) -> bool =
+        <TallySystem as ApplyTallySystem>::is_ready;
+
+    let context = ::sillyecs::FrameContext::new(<MainWorld<NoOpPhaseEvents, CommandQueue> as ::sillyecs::World>::ID);
+    assert!(bounce(&BounceSystem::default()));
+    assert!(tally(&TallySystem::default(), &context));
+}
+
+#[test]
+fn rng_seed_reaches_frame_context() {
+    let mut world: MainWorld<NoOpPhaseEvents, CommandQueue> =
+        MainWorld::new(&SystemFactory, MainWorldStates::default(), CommandQueue::new());
+    assert_eq!(world.context.rng_seed, 0);
+    world.set_rng_seed(7);
+    world.apply_system_phases();
+    assert_eq!(world.context.rng_seed, 7);
+}
+
+#[test]
+fn component_slices_by_archetype_reassemble_flattened_order() {
+    let mut world: MainWorld<NoOpPhaseEvents, CommandQueue> =
+        MainWorld::new(&SystemFactory, MainWorldStates::default(), CommandQueue::new());
+    for i in 0..3 {
+        let _ = world.spawn_decoration_with(
+            PositionComponent::new(PositionData { x: 10.0 + i as f32, y: 0.0 }),
+            SpriteComponent::new(SpriteData(i)),
+        );
+        let _ = world.spawn_particle(ParticleEntityComponents {
+            position: PositionComponent::new(PositionData { x: i as f32, y: 0.0 }),
+            velocity: VelocityComponent::new(VelocityData::default()),
+        });
+    }
+
+    let archetypes: Vec<_> = world.positions_by_archetype().map(|(id, slice)| (id, slice.len())).collect();
+    assert_eq!(
+        archetypes,
+        [
+            (ArchetypeId::Particle, 3),
+            (ArchetypeId::LivingParticle, 0),
+            (ArchetypeId::Decoration, 3),
+        ]
+    );
+
+    let chunked: Vec<f32> = world
+        .positions_by_archetype()
+        .flat_map(|(_, slice)| slice.iter().map(|position| position.x))
+        .collect();
+    let flattened: Vec<f32> = world.iter_positions().map(|position| position.x).collect();
+    assert_eq!(chunked, flattened);
+    assert_eq!(chunked, [0.0, 1.0, 2.0, 10.0, 11.0, 12.0]);
+}
+
+#[test]
+fn comparable_archetype_bundles_implement_partial_eq() {
+    let bundle = |x: f32| ParticleEntityComponents {
+        position: PositionComponent::new(PositionData { x, y: 1.0 }),
+        velocity: VelocityComponent::new(VelocityData { x: 0.0, y: -1.0 }),
+    };
+    assert_eq!(bundle(1.0), bundle(1.0));
+    assert_ne!(bundle(1.0), bundle(2.0));
+
+    let data = |x: f32| ParticleEntityData {
+        position: PositionData { x, y: 0.0 },
+        velocity: VelocityData::default(),
+    };
+    assert_eq!(data(3.0), data(3.0));
+    assert_ne!(data(3.0), data(4.0));
+}
+
+#[test]
+fn spawn_and_despawn_counters_track_lifetime_churn() {
+    let mut world: MainWorld<NoOpPhaseEvents, CommandQueue> =
+        MainWorld::new(&SystemFactory, MainWorldStates::default(), CommandQueue::new());
+    let ids: Vec<_> = (0..5)
+        .map(|i| {
+            world.spawn_decoration_with(
+                PositionComponent::new(PositionData::default()),
+                SpriteComponent::new(SpriteData(i)),
+            )
+        })
+        .collect();
+    world.despawn_by_id(ids[1]).unwrap();
+    world.despawn_by_id(ids[3]).unwrap();
+    assert!(world.despawn_by_id(ids[3]).is_err());
+
+    assert_eq!(world.total_spawned(), 5);
+    assert_eq!(world.total_despawned(), 2);
+    assert_eq!(world.len(), 3);
+    assert_eq!(world.total_spawned_decorations(), 5);
+    assert_eq!(world.total_despawned_decorations(), 2);
+    assert_eq!(world.total_spawned_particles(), 0);
+}
+
+#[test]
+fn stepping_frame_plan_runs_all_systems_once() {
+    let mut world: MainWorld<NoOpPhaseEvents, CommandQueue> =
+        MainWorld::new(&SystemFactory, MainWorldStates::default(), CommandQueue::new());
+    world.request_update_phase();
+
+    let plan = world.begin_frame(SystemPhase::FIXED_UPDATE_SECS);
+    let batches: Vec<FrameBatch> = plan.collect();
+    let phases: Vec<SystemPhase> = batches.iter().map(FrameBatch::phase).collect();
+    assert_eq!(
+        phases,
+        [
+            SystemPhase::FixedUpdate,
+            SystemPhase::FixedUpdate,
+            SystemPhase::Update
+        ],
+        "one batch per system group"
+    );
+
+    let (first, rest) = batches.split_first().unwrap();
+    world.run_frame_batch(*first);
+    assert_eq!(world.systems.as_step_ref().runs, 1);
+    assert_eq!(
+        world.systems.as_tally_ref().runs,
+        0,
+        "the world is released between the groups of a phase"
+    );
+    for batch in rest {
+        world.run_frame_batch(*batch);
+    }
+    world.end_frame();
+
+    assert_eq!(world.systems.as_step_ref().runs, 1);
+    assert_eq!(world.systems.as_tally_ref().runs, 1);
+    assert_eq!(
+        world.systems.as_tally_ref().delta_time_secs,
+        Some(SystemPhase::FIXED_UPDATE_SECS)
+    );
+
+    // Without a due fixed step or a pending request, only the variable-rate pass remains.
+    let plan = world.begin_frame(0.0);
+    assert_eq!(plan.len(), 2);
+    for batch in plan {
+        world.par_run_frame_batch(batch);
+    }
+    world.end_frame();
+    assert_eq!(world.systems.as_step_ref().runs, 1);
+    assert_eq!(world.systems.as_tally_ref().runs, 2);
+}
+
+/// Phase events that hand the frame number from the beginning of a fixed step to its end.
+#[derive(Debug, Default)]
+struct FixedStepEvents {
+    /// The results the end of each fixed step received.
+    ended: Vec<Option<u64>>,
+}
+
+impl SystemFixedUpdatePhaseEvents for FixedStepEvents {
+    type Result = u64;
+    type Error = core::convert::Infallible;
+
+    fn on_begin_phase(&mut self, context: &::sillyecs::FrameContext) -> Result<u64, Self::Error> {
+        Ok(context.frame_number)
+    }
+
+    fn on_end_phase(
+        &mut self,
+        result: Option<u64>,
+        _context: &::sillyecs::FrameContext,
+    ) -> Result<(), Self::Error> {
+        self.ended.push(result);
+        Ok(())
+    }
+}
+
+macro_rules! impl_no_op_phase_events {
+    ($($events:ident),*) => {$(
+        impl $events for FixedStepEvents {
+            type Result = ();
+            type Error = core::convert::Infallible;
+
+            fn on_begin_phase(
+                &mut self,
+                _context: &::sillyecs::FrameContext,
+            ) -> Result<(), Self::Error> {
+                Ok(())
+            }
+
+            fn on_end_phase(
+                &mut self,
+                _result: Option<()>,
+                _context: &::sillyecs::FrameContext,
+            ) -> Result<(), Self::Error> {
+                Ok(())
+            }
+        }
+    )*};
+}
+
+impl_no_op_phase_events!(
+    SystemBootPhaseEvents,
+    SystemUpdatePhaseEvents,
+    SystemForcesPhaseEvents
+);
+
+impl SystemRenderPhaseEvents for FixedStepEvents {
+    type Result = ();
+    type Error = core::convert::Infallible;
+
+    fn on_begin_phase(
+        &mut self,
+        _context: &::sillyecs::FrameContext,
+        _renderer: &mut RendererState,
+    ) -> Result<(), Self::Error> {
+        Ok(())
+    }
+
+    fn on_end_phase(
+        &mut self,
+        _result: Option<()>,
+        _context: &::sillyecs::FrameContext,
+        _renderer: &mut RendererState,
+    ) -> Result<(), Self::Error> {
+        Ok(())
+    }
+}
+
+#[test]
+fn frame_batches_hand_the_phase_result_from_first_to_last_group() {
+    let mut world: MainWorld<FixedStepEvents, CommandQueue> = MainWorld::new_with_events(
+        &SystemFactory,
+        MainWorldStates::default(),
+        CommandQueue::new(),
+        FixedStepEvents::default(),
+    );
+
+    let plan = world.begin_frame(2.0 * SystemPhase::FIXED_UPDATE_SECS);
+    assert_eq!(plan.len(), 4, "two steps of two groups each");
+    for batch in plan {
+        world.par_run_frame_batch(batch);
+    }
+    world.end_frame();
+
+    assert_eq!(world.events.ended, [Some(1), Some(1)]);
+    assert_eq!(world.systems.as_step_ref().runs, 2);
+}
+
+#[test]
+fn tick_fixed_runs_only_fixed_phases() {
+    let mut world: MainWorld<NoOpPhaseEvents, CommandQueue> =
+        MainWorld::new(&SystemFactory, MainWorldStates::default(), CommandQueue::new());
+    world.request_update_phase();
+
+    world.tick_fixed(2.5 * SystemPhase::FIXED_UPDATE_SECS);
+    assert_eq!(world.systems.as_step_ref().runs, 2);
+    assert_eq!(world.systems.as_tally_ref().runs, 1);
+
+    // The remaining half step is carried over to the next tick.
+    world.par_tick_fixed(0.6 * SystemPhase::FIXED_UPDATE_SECS);
+    assert_eq!(world.systems.as_step_ref().runs, 3);
+    assert_eq!(world.systems.as_tally_ref().runs, 2);
+
+    // The variable-time Update phase never ran, so its request is still pending.
+    assert!(world.phase_flags.is_update_requested());
+}
+
+#[test]
+fn accumulating_systems_run_in_shared_parallel_group() {
+    let mut world: MainWorld<NoOpPhaseEvents, CommandQueue> =
+        MainWorld::new(&SystemFactory, MainWorldStates::default(), CommandQueue::new());
+    let id = world.spawn_particle(ParticleEntityComponents {
+        position: PositionComponent::new(PositionData::default()),
+        velocity: VelocityComponent::new(VelocityData::default()),
+    });
+
+    world.par_apply_system_phase_forces();
+    let velocity = ComponentAccess::get_velocity_component(&world, id).unwrap();
+    assert_eq!(**velocity, VelocityData { x: 0.5, y: -0.5 });
+
+    world.apply_system_phase_forces();
+    let velocity = ComponentAccess::get_velocity_component(&world, id).unwrap();
+    assert_eq!(**velocity, VelocityData { x: 0.75, y: -0.75 });
+}
+
+#[test]
+fn retain_living_particle_keeps_healthy_entities_addressable() {
+    let mut world: MainWorld<NoOpPhaseEvents, CommandQueue> =
+        MainWorld::new(&SystemFactory, MainWorldStates::default(), CommandQueue::new());
+
+    let spawned: Vec<_> = [3, 0, 5, -1, 2]
+        .into_iter()
+        .map(|health| {
+            let id = world.spawn_living_particle(LivingParticleEntityComponents {
+                position: PositionComponent::new(PositionData::default()),
+                velocity: VelocityComponent::new(VelocityData::default()),
+                health: HealthComponent::new(HealthData(health)),
+            });
+            (id, health)
+        })
+        .collect();
+
+    world.retain_living_particle(|_, components| (*components.health).0 > 0);
+
+    assert_eq!(world.len(), 3);
+    assert_eq!(world.total_despawned_living_particles(), 2);
+    for &(id, health) in &spawned {
+        let stored = ComponentAccess::get_health_component(&world, id);
+        if health > 0 {
+            assert_eq!((**stored.expect("survivor must be addressable")).0, health);
+        } else {
+            assert!(stored.is_none());
+        }
+    }
+
+    let (last, _) = spawned[4];
+    assert!(world.despawn_by_id(last).is_ok());
+    let (first, _) = spawned[0];
+    assert_eq!((**ComponentAccess::get_health_component(&world, first).unwrap()).0, 3);
+}
+
+#[test]
+fn swap_particle_exchanges_storage_slots_and_locations() {
+    let mut world: MainWorld<NoOpPhaseEvents, CommandQueue> =
+        MainWorld::new(&SystemFactory, MainWorldStates::default(), CommandQueue::new());
+
+    let [a, b] = [1.0, 2.0].map(|x| {
+        world.spawn_particle(ParticleEntityComponents {
+            position: PositionComponent::new(PositionData { x, y: 0.0 }),
+            velocity: VelocityComponent::new(VelocityData { x: 0.0, y: x }),
+        })
+    });
+    let decoration = world.spawn_decoration(DecorationEntityComponents {
+        position: PositionComponent::new(PositionData::default()),
+        sprite: SpriteComponent::new(SpriteData(1)),
+    });
+
+    assert!(!world.swap_particle(a, decoration));
+    assert!(world.swap_particle(a, b));
+
+    let particles = &world.archetypes.collection.particle;
+    assert_eq!(particles.entities, [b, a]);
+    assert_eq!(particles.positions[0].x, 2.0);
+    assert_eq!(particles.velocities[1].y, 1.0);
+    assert_eq!(world.archetypes.entity_locations[&a].index, 1);
+    assert_eq!(world.archetypes.entity_locations[&b].index, 0);
+    assert_eq!(ComponentAccess::get_position_component(&world, a).unwrap().x, 1.0);
+    assert_eq!(ComponentAccess::get_velocity_component(&world, b).unwrap().y, 2.0);
+}
+
+#[test]
+fn archetype_edges_are_symmetric() {
+    assert_eq!(
+        ArchetypeId::Particle.with_component(ComponentId::Health),
+        Some(ArchetypeId::LivingParticle)
+    );
+    assert_eq!(
+        ArchetypeId::LivingParticle.without_component(ComponentId::Health),
+        Some(ArchetypeId::Particle)
+    );
+    assert_eq!(ArchetypeId::Particle.with_component(ComponentId::Sprite), None);
+    assert_eq!(ArchetypeId::Particle.without_component(ComponentId::Velocity), None);
+    assert_eq!(ArchetypeId::Decoration.with_component(ComponentId::Health), None);
+
+    let archetypes = [
+        ArchetypeId::Particle,
+        ArchetypeId::LivingParticle,
+        ArchetypeId::Decoration,
+    ];
+    for archetype in archetypes {
+        for &(name, _) in COMPONENTS {
+            let component = component_id_by_name(name).unwrap();
+            if let Some(target) = archetype.with_component(component) {
+                assert_eq!(target.without_component(component), Some(archetype));
+            }
+            if let Some(target) = archetype.without_component(component) {
+                assert_eq!(target.with_component(component), Some(archetype));
+            }
+        }
+    }
+}
+
+#[test]
+#[cfg(debug_assertions)]
+fn validate_invariants_detects_corrupted_columns() {
+    let mut world: MainWorld<NoOpPhaseEvents, CommandQueue> =
+        MainWorld::new(&SystemFactory, MainWorldStates::default(), CommandQueue::new());
+    assert_eq!(world.validate_invariants(), Ok(()));
+
+    let ids: Vec<_> = (0..3)
+        .map(|i| {
+            world.spawn_particle(ParticleEntityComponents {
+                position: PositionComponent::new(PositionData { x: i as f32, y: 0.0 }),
+                velocity: VelocityComponent::new(VelocityData::default()),
+            })
+        })
+        .collect();
+    world.despawn_by_id(ids[0]).unwrap();
+    assert_eq!(world.validate_invariants(), Ok(()));
+
+    world.archetypes.collection.particle.positions.pop();
+    let violation = world.validate_invariants().unwrap_err();
+    assert!(violation.contains("Position"), "{violation}");
+}
+
+#[test]
+fn spawn_accepts_entity_data_and_components() {
+    let mut world: MainWorld<NoOpPhaseEvents, CommandQueue> =
+        MainWorld::new(&SystemFactory, MainWorldStates::default(), CommandQueue::new());
+
+    let from_data = world.spawn(ParticleEntityData {
+        position: PositionData { x: 1.0, y: 0.0 },
+        velocity: VelocityData::default(),
+    });
+    let from_components = world.spawn(DecorationEntityComponents {
+        position: PositionComponent::new(PositionData { x: 2.0, y: 0.0 }),
+        sprite: SpriteComponent::new(SpriteData(7)),
+    });
+    let through_archetype = world.spawn_particle(ParticleEntityData {
+        position: PositionData { x: 3.0, y: 0.0 },
+        velocity: VelocityData::default(),
+    });
+
+    assert_eq!(world.len(), 3);
+    for (id, x) in [(from_data, 1.0), (from_components, 2.0), (through_archetype, 3.0)] {
+        assert_eq!(ComponentAccess::get_position_component(&world, id).unwrap().x, x);
+    }
+}
+
+#[test]
+fn iter_all_views_every_entity() {
+    let mut world: MainWorld<NoOpPhaseEvents, CommandQueue> =
+        MainWorld::new(&SystemFactory, MainWorldStates::default(), CommandQueue::new());
+
+    let particle = world.spawn(ParticleEntityData {
+        position: PositionData { x: 1.0, y: 0.0 },
+        velocity: VelocityData::default(),
+    });
+    let decoration = world.spawn(DecorationEntityData {
+        position: PositionData { x: 2.0, y: 0.0 },
+        sprite: SpriteData(7),
+    });
+
+    let views: Vec<_> = world.iter_all().collect();
+    assert_eq!(views.len(), world.len());
+    for view in &views {
+        assert!(view.get_position_component().is_some());
+    }
+
+    let view = views.iter().find(|view| view.entity_id() == particle).unwrap();
+    assert_eq!(view.archetype_name(), "Particle");
+    assert_eq!(view.archetype_id(), ParticleArchetype::ID);
+    assert!(view.get_velocity_component().is_some());
+    assert!(view.get_sprite_component().is_none());
+
+    let view = views.iter().find(|view| view.entity_id() == decoration).unwrap();
+    assert_eq!(view.archetype_name(), "Decoration");
+    assert_eq!(view.get_position_component().unwrap().x, 2.0);
+    assert_eq!((**view.get_sprite_component().unwrap()).0, 7);
+}
+
+#[test]
+fn iter_archetype_borrows_all_components_per_entity() {
+    let mut world: MainWorld<NoOpPhaseEvents, CommandQueue> =
+        MainWorld::new(&SystemFactory, MainWorldStates::default(), CommandQueue::new());
+
+    let first = world.spawn(DecorationEntityData {
+        position: PositionData { x: 1.0, y: 0.0 },
+        sprite: SpriteData(3),
+    });
+    let second = world.spawn(DecorationEntityData {
+        position: PositionData { x: 2.0, y: 0.0 },
+        sprite: SpriteData(7),
+    });
+    world.spawn(ParticleEntityData {
+        position: PositionData { x: 9.0, y: 0.0 },
+        velocity: VelocityData::default(),
+    });
+
+    let decorations: Vec<_> = world
+        .iter_decoration()
+        .map(|(id, entity)| (id, entity.position.x, (**entity.sprite).0))
+        .collect();
+    assert_eq!(decorations, vec![(first, 1.0, 3), (second, 2.0, 7)]);
+}
+
+#[test]
+fn visitor_only_sees_the_archetypes_it_implements() {
+    #[derive(Default)]
+    struct DecorationCounter {
+        ids: Vec<sillyecs::EntityId>,
+        sprites: u32,
+    }
+
+    impl WorldVisitor for DecorationCounter {
+        fn visit_decoration(&mut self, id: sillyecs::EntityId, entity: &DecorationEntityRef<'_>) {
+            self.ids.push(id);
+            self.sprites += (**entity.sprite).0;
+        }
+    }
+
+    let mut world: MainWorld<NoOpPhaseEvents, CommandQueue> =
+        MainWorld::new(&SystemFactory, MainWorldStates::default(), CommandQueue::new());
+    let decoration = world.spawn(DecorationEntityData {
+        position: PositionData { x: 1.0, y: 0.0 },
+        sprite: SpriteData(3),
+    });
+    for _ in 0..3 {
+        world.spawn(ParticleEntityData {
+            position: PositionData::default(),
+            velocity: VelocityData::default(),
+        });
+    }
+
+    let mut counter = DecorationCounter::default();
+    world.accept(&mut counter);
+    assert_eq!(counter.ids, vec![decoration]);
+    assert_eq!(counter.sprites, 3);
+}
+
+#[test]
+fn component_map_looks_up_entities() {
+    let mut world: MainWorld<NoOpPhaseEvents, CommandQueue> =
+        MainWorld::new(&SystemFactory, MainWorldStates::default(), CommandQueue::new());
+
+    let particle = world.spawn(ParticleEntityData {
+        position: PositionData { x: 1.0, y: 0.0 },
+        velocity: VelocityData::default(),
+    });
+    let decoration = world.spawn(DecorationEntityData {
+        position: PositionData { x: 2.0, y: 0.0 },
+        sprite: SpriteData(7),
+    });
+
+    let positions = world.position_map();
+    assert_eq!(positions.len(), 2);
+    assert_eq!(positions[&particle].x, 1.0);
+    assert_eq!(positions[&decoration].x, 2.0);
+
+    let sprites = world.sprite_map();
+    assert_eq!(sprites.len(), 1);
+    assert_eq!((**sprites[&decoration]).0, 7);
+    assert!(!sprites.contains_key(&particle));
+}
+
+#[test]
+fn visit_component_mut_reaches_every_archetype() {
+    let mut world: MainWorld<NoOpPhaseEvents, CommandQueue> =
+        MainWorld::new(&SystemFactory, MainWorldStates::default(), CommandQueue::new());
+
+    let particle = world.spawn(ParticleEntityData {
+        position: PositionData { x: 1.0, y: 0.0 },
+        velocity: VelocityData::default(),
+    });
+    let decoration = world.spawn(DecorationEntityData {
+        position: PositionData { x: 2.0, y: 0.0 },
+        sprite: SpriteData(7),
+    });
+
+    let mut visited = Vec::new();
+    world.visit_position_mut(|id, position| {
+        position.x *= 2.0;
+        visited.push(id);
+    });
+
+    assert_eq!(visited.len(), 2);
+    let positions = world.position_map();
+    assert_eq!(positions[&particle].x, 2.0);
+    assert_eq!(positions[&decoration].x, 4.0);
+}
+
+#[test]
+fn entity_data_converts_along_promotions() {
+    let particle = ParticleEntityData {
+        position: PositionData { x: 1.0, y: 2.0 },
+        velocity: VelocityData::default(),
+    };
+    let living = LivingParticleEntityData::from_particle(particle, HealthData(5));
+    assert_eq!(living.position.x, 1.0);
+    assert_eq!(living.position.y, 2.0);
+    assert_eq!(living.health.0, 5);
+
+    let mut world: MainWorld<NoOpPhaseEvents, CommandQueue> =
+        MainWorld::new(&SystemFactory, MainWorldStates::default(), CommandQueue::new());
+    let id = world.spawn(living);
+    assert_eq!((**ComponentAccess::get_health_component(&world, id).unwrap()).0, 5);
+}
+
+#[test]
+fn stepping_phases_matches_applying_them() {
+    let spawn = |world: &mut MainWorld<NoOpPhaseEvents, CommandQueue>| {
+        let _ = world.spawn(ParticleEntityData {
+            position: PositionData { x: 1.0, y: -2.0 },
+            velocity: VelocityData { x: 0.5, y: 3.0 },
+        });
+        let _ = world.spawn(LivingParticleEntityData {
+            position: PositionData { x: -1.0, y: 4.0 },
+            velocity: VelocityData { x: 2.0, y: -1.0 },
+            health: HealthData(3),
+        });
+    };
+
+    let mut applied: MainWorld<NoOpPhaseEvents, CommandQueue> =
+        MainWorld::new(&SystemFactory, MainWorldStates::default(), CommandQueue::new());
+    spawn(&mut applied);
+    applied.apply_system_phase_forces();
+    applied.request_update_phase();
+    applied.apply_system_phases();
+
+    let mut stepped: MainWorld<NoOpPhaseEvents, CommandQueue> =
+        MainWorld::new(&SystemFactory, MainWorldStates::default(), CommandQueue::new());
+    spawn(&mut stepped);
+    let context = ::sillyecs::FrameContext::new(
+        <MainWorld<NoOpPhaseEvents, CommandQueue> as ::sillyecs::World>::ID,
+    );
+    for phase in [SystemPhase::Forces, SystemPhase::Update] {
+        stepped.step_phase(phase, &context);
+    }
+
+    let snapshot = |world: &MainWorld<NoOpPhaseEvents, CommandQueue>| {
+        world
+            .iter_all()
+            .map(|view| {
+                let position = view.get_position_component().unwrap();
+                let velocity = view.get_velocity_component().unwrap();
+                let health = view.get_health_component().map(|health| (**health).0);
+                (position.x, position.y, velocity.x, velocity.y, health)
+            })
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(snapshot(&stepped), snapshot(&applied));
+    assert_ne!(
+        snapshot(&stepped)[0],
+        (1.0, -2.0, 0.5, 3.0, None),
+        "the systems must have run"
+    );
+}
+
+#[test]
+fn stepping_a_phase_uses_the_supplied_context() {
+    let mut world: MainWorld<NoOpPhaseEvents, CommandQueue> =
+        MainWorld::new(&SystemFactory, MainWorldStates::default(), CommandQueue::new());
+    let context = ::sillyecs::FrameContext::builder(
+        <MainWorld<NoOpPhaseEvents, CommandQueue> as ::sillyecs::World>::ID,
+    )
+    .frame_number(3)
+    .delta_time_secs(0.25)
+    .build();
+
+    world.step_phase(SystemPhase::FixedUpdate, &context);
+    assert_eq!(world.systems.as_tally_ref().delta_time_secs, Some(0.25));
+    assert_eq!(world.systems.as_tally_ref().frame_number, Some(3));
+    assert_eq!(world.context.frame_number, 0, "the world context is restored");
+
+    let mut halved = context.clone();
+    halved.delta_time_secs = 0.5;
+    world.par_step_phase(SystemPhase::FixedUpdate, &halved);
+    assert_eq!(world.systems.as_tally_ref().delta_time_secs, Some(0.5));
+}
+
+#[test]
+fn component_masks_follow_component_ids() {
+    let has = |mask: [u8; ComponentId::MASK_BYTES], component: ComponentId| {
+        let bit = component.as_u64() as usize - 1;
+        mask[bit / 8] & (1 << (bit % 8)) != 0
+    };
+
+    let particle = ArchetypeId::Particle.component_mask();
+    let living = ArchetypeId::LivingParticle.component_mask();
+    assert_eq!(particle, ParticleArchetype::MASK);
+    assert_eq!(ComponentId::MASK_BYTES, COMPONENTS.len().div_ceil(8));
+
+    for &(name, _) in COMPONENTS {
+        let component = component_id_by_name(name).unwrap();
+        let in_particle = matches!(component, ComponentId::Position | ComponentId::Velocity);
+        assert_eq!(has(particle, component), in_particle, "{name}");
+        assert_eq!(has(living, component), in_particle || component == ComponentId::Health, "{name}");
+    }
+
+    let difference: Vec<_> = particle.iter().zip(living).map(|(a, b)| a ^ b).collect();
+    let health = ComponentId::Health.as_u64() as usize - 1;
+    let mut expected = vec![0; ComponentId::MASK_BYTES];
+    expected[health / 8] = 1 << (health % 8);
+    assert_eq!(difference, expected);
+}
+
+#[test]
+fn affected_archetypes_follow_system_inputs_and_outputs() {
+    assert_eq!(
+        <StepSystem as System>::AFFECTED_ARCHETYPES,
+        &["LivingParticle", "Particle"]
+    );
+    assert_eq!(<AttractSystem as System>::AFFECTED_ARCHETYPES, &["LivingParticle"]);
+    assert_eq!(<DrawSystem as System>::AFFECTED_ARCHETYPES, &["Decoration"]);
+    assert_eq!(
+        SystemId::Heal.affected_archetypes(),
+        <HealSystem as System>::AFFECTED_ARCHETYPES
+    );
+
+    let world: MainWorld<NoOpPhaseEvents, CommandQueue> =
+        MainWorld::new(&SystemFactory, MainWorldStates::default(), CommandQueue::new());
+    assert_eq!(
+        world.archetypes_affected_by(SystemId::Bounce),
+        &["LivingParticle", "Particle"]
+    );
+}
+
+#[test]
+fn entity_ids_in_looks_up_archetypes_by_name() {
+    let mut world: MainWorld<NoOpPhaseEvents, CommandQueue> =
+        MainWorld::new(&SystemFactory, MainWorldStates::default(), CommandQueue::new());
+    let decoration = world.spawn_decoration(DecorationEntityComponents {
+        position: PositionComponent::new(PositionData::default()),
+        sprite: SpriteComponent::new(SpriteData(0)),
+    });
+
+    assert_eq!(world.entity_ids_in("Decoration"), Some(&[decoration][..]));
+    assert_eq!(world.entity_ids_in("Particle"), Some(&[][..]));
+    assert_eq!(world.entity_ids_in("DecorationArchetype"), None);
+    assert_eq!(world.entity_ids_in("Unknown"), None);
+}
+
+#[test]
+fn component_ids_are_sorted_per_archetype() {
+    let expected = [
+        ComponentId::Position as u32,
+        ComponentId::Velocity as u32,
+        ComponentId::Health as u32,
+    ];
+    assert_eq!(LivingParticleArchetype::COMPONENT_IDS, &expected);
+    assert_eq!(ArchetypeId::LivingParticle.component_ids(), &expected);
+    assert!(ArchetypeId::Decoration.component_ids().is_sorted());
+
+    let world: MainWorld<NoOpPhaseEvents, CommandQueue> =
+        MainWorld::new(&SystemFactory, MainWorldStates::default(), CommandQueue::new());
+    assert_eq!(world.component_ids_of(ArchetypeId::Decoration), &[1, 4]);
+}
+
+#[test]
+fn apply_command_queue_handles_queued_commands_outside_phases() {
+    let mut world: MainWorld<NoOpPhaseEvents, CommandQueue> =
+        MainWorld::new(&SystemFactory, MainWorldStates::default(), CommandQueue::new());
+    let decoration = world.spawn_decoration(DecorationEntityComponents {
+        position: PositionComponent::new(PositionData::default()),
+        sprite: SpriteComponent::new(SpriteData(0)),
+    });
+
+    world
+        .command(WorldCommand::SpawnEntity(ArchetypeEntityData::Particle(ParticleEntityData {
+            position: PositionData::default(),
+            velocity: VelocityData::default(),
+        })))
+        .unwrap();
+    world.command(WorldCommand::DespawnEntity(decoration)).unwrap();
+    world.command(WorldCommand::User(UserCommand::Heal { amount: 1 })).unwrap();
+    assert_eq!(world.len(), 1);
+    assert!(world.archetypes.collection.particle.is_empty());
+
+    world.apply_command_queue();
+    assert_eq!(world.len(), 1);
+    assert_eq!(world.archetypes.collection.particle.len(), 1);
+    assert!(world.archetypes.collection.decoration.is_empty());
+}
+
+#[test]
+fn position_reflects_its_declared_fields() {
+    assert_eq!(PositionComponent::FIELDS, &[("x", "f32"), ("y", "f32")]);
+}
+
+#[test]
+fn velocity_storage_is_aligned() {
+    let mut world: MainWorld<NoOpPhaseEvents, CommandQueue> =
+        MainWorld::new(&SystemFactory, MainWorldStates::default(), CommandQueue::new());
+
+    for i in 0..10 {
+        let _ = world.spawn_particle(ParticleEntityComponents {
+            position: PositionComponent::new(PositionData::default()),
+            velocity: VelocityComponent::new(VelocityData { x: i as f32, y: 0.0 }),
+        });
+        let velocities = &world.archetypes.collection.particle.velocities;
+        assert_eq!(velocities.as_ptr() as usize % 32, 0);
+    }
+    assert_eq!(world.archetypes.collection.particle.velocities[9].x, 9.0);
+}
+
+#[test]
+fn fork_copies_entities_under_fresh_ids() {
+    let mut world: MainWorld<NoOpPhaseEvents, CommandQueue> =
+        MainWorld::new(&SystemFactory, MainWorldStates::default(), CommandQueue::new());
+    let particles: Vec<_> = (0..3)
+        .map(|i| {
+            world.spawn_particle(ParticleEntityComponents {
+                position: PositionComponent::new(PositionData { x: i as f32, y: 0.0 }),
+                velocity: VelocityComponent::new(VelocityData::default()),
+            })
+        })
+        .collect();
+    let decoration = world.spawn_decoration(DecorationEntityComponents {
+        position: PositionComponent::new(PositionData { x: 5.0, y: 0.0 }),
+        sprite: SpriteComponent::new(SpriteData(1)),
+    });
+
+    let (mut fork, ids) = world.fork();
+    assert_eq!(fork.len(), 4);
+    assert_eq!(ids.len(), 4);
+    assert_eq!(fork.total_spawned_particles(), 3);
+    fork.validate_invariants().expect("the fork is consistent");
+
+    for &id in particles.iter().chain([&decoration]) {
+        let fork_id = ids[&id];
+        assert_ne!(fork_id, id);
+        // Debug builds panic instead, see `looking_up_an_original_entity_in_its_fork_panics_in_debug`.
+        #[cfg(not(debug_assertions))]
+        assert!(ComponentAccess::get_position_component(&fork, id).is_none());
+        assert_eq!(
+            ComponentAccess::get_position_component(&fork, fork_id).unwrap().x,
+            ComponentAccess::get_position_component(&world, id).unwrap().x
+        );
+    }
+
+    ComponentAccessMut::get_position_component_mut(&mut fork, ids[&particles[1]])
+        .unwrap()
+        .x = 42.0;
+    fork.despawn_by_id(ids[&decoration]).unwrap();
+    assert_eq!(ComponentAccess::get_position_component(&world, particles[1]).unwrap().x, 1.0);
+    assert_eq!(world.len(), 4);
+}
+
+#[test]
+#[cfg(debug_assertions)]
+#[should_panic(expected = "but was used with the `Main` world instance")]
+fn looking_up_an_original_entity_in_its_fork_panics_in_debug() {
+    let mut world: MainWorld<NoOpPhaseEvents, CommandQueue> =
+        MainWorld::new(&SystemFactory, MainWorldStates::default(), CommandQueue::new());
+    let decoration = world.spawn_decoration(DecorationEntityComponents {
+        position: PositionComponent::new(PositionData { x: 5.0, y: 0.0 }),
+        sprite: SpriteComponent::new(SpriteData(1)),
+    });
+
+    let (fork, ids) = world.fork();
+    assert_ne!(ids[&decoration].world_tag(), decoration.world_tag());
+    let _ = ComponentAccess::get_position_component(&fork, decoration);
+}
+
+#[test]
+fn component_results_tell_missing_entities_from_missing_components() {
+    let mut world: MainWorld<NoOpPhaseEvents, CommandQueue> =
+        MainWorld::new(&SystemFactory, MainWorldStates::default(), CommandQueue::new());
+    let decoration = world.spawn_decoration(DecorationEntityComponents {
+        position: PositionComponent::new(PositionData { x: 5.0, y: 0.0 }),
+        sprite: SpriteComponent::new(SpriteData(1)),
+    });
+
+    assert_eq!(world.get_position_component_res(decoration).unwrap().x, 5.0);
+    world.get_position_component_res_mut(decoration).unwrap().x = 6.0;
+    assert_eq!(ComponentAccess::get_position_component(&world, decoration).unwrap().x, 6.0);
+
+    let missing = world.get_velocity_component_res(decoration).unwrap_err();
+    assert_eq!(missing, EntityError::MissingComponent(decoration, ComponentId::Velocity));
+    assert_eq!(missing.to_string(), format!("Entity {decoration} has no component Velocity (ID 2)"));
+
+    world.despawn_by_id(decoration).unwrap();
+    assert_eq!(
+        world.get_position_component_res(decoration).unwrap_err(),
+        EntityError::EntityNotFound(decoration)
+    );
+    assert_eq!(
+        world.get_velocity_component_res_mut(decoration).unwrap_err(),
+        EntityError::EntityNotFound(decoration)
+    );
+}
+
+#[test]
+fn run_system_runs_only_the_named_system() {
+    let mut world: MainWorld<NoOpPhaseEvents, CommandQueue> =
+        MainWorld::new(&SystemFactory, MainWorldStates::default(), CommandQueue::new());
+    let particle = world.spawn_particle(ParticleEntityComponents {
+        position: PositionComponent::new(PositionData::default()),
+        velocity: VelocityComponent::new(VelocityData { x: 2.0, y: 2.0 }),
+    });
+    let context = ::sillyecs::FrameContext::builder(
+        <MainWorld<NoOpPhaseEvents, CommandQueue> as ::sillyecs::World>::ID,
+    )
+    .frame_number(7)
+    .build();
+
+    assert!(world.run_system("Wind", &context));
+    let velocity = ComponentAccess::get_velocity_component(&world, particle).unwrap();
+    assert_eq!((velocity.x, velocity.y), (3.0, 2.0));
+
+    assert!(world.run_system("Step", &context));
+    assert_eq!(world.systems.as_step_ref().runs, 0);
+    assert_eq!(world.context.frame_number, 0);
+
+    assert!(!world.run_system("Unknown", &context));
+    assert!(!world.run_system("WindSystem", &context));
+    let velocity = ComponentAccess::get_velocity_component(&world, particle).unwrap();
+    assert_eq!((velocity.x, velocity.y), (3.0, 2.0));
+}
@@ -16,13 +16,13 @@ pub type EntityLocationMap<K, V> = HashMap<K, V>;
 // `Deref<Target = XData>` etc., so each component named in the YAML needs a
 // matching `XData` type that derives `Debug + Clone + Default`.
 
-#[derive(Debug, Default, Clone)]
+#[derive(Debug, Default, Clone, PartialEq)]
 pub struct PositionData {
     pub x: f32,
     pub y: f32,
 }
 
-#[derive(Debug, Default, Clone)]
+#[derive(Debug, Default, Clone, PartialEq)]
 pub struct VelocityData {
     pub x: f32,
     pub y: f32,
@@ -36,26 +36,81 @@ pub struct SpriteData(pub u32);
 
 // --- States -------------------------------------------------------------------
 
-#[derive(Debug, Default)]
+#[derive(Debug, Default, Clone)]
 pub struct InputState;
 
-#[derive(Debug, Default)]
+#[derive(Debug, Default, Clone)]
 pub struct RendererState;
 
 // --- System data + Default for system newtypes --------------------------------
 
 #[derive(Debug, Default)]
-pub struct StepSystemData;
+pub struct AttractSystemData {
+    /// The entity whose position is pulled along by every `LivingParticle`.
+    pub target: Option<::sillyecs::EntityId>,
+    /// An entity of an iterated archetype; must not be reachable through the lookup.
+    pub probe: Option<::sillyecs::EntityId>,
+    pub probe_reachable: bool,
+}
+
+#[derive(Debug, Default)]
+pub struct StepSystemData {
+    /// The number of fixed steps this system ran.
+    pub runs: usize,
+}
+
+#[derive(Debug, Default)]
+pub struct TallySystemData {
+    /// The number of frames this system ran.
+    pub runs: usize,
+    /// The delta time of the frame context this system last saw.
+    pub delta_time_secs: Option<f32>,
+    /// The frame number of the frame context this system last saw.
+    pub frame_number: Option<u64>,
+}
+
+#[derive(Debug, Default)]
+pub struct BounceSystemData;
 
 #[derive(Debug, Default)]
 pub struct HealSystemData;
 
 #[derive(Debug, Default)]
-pub struct DrawSystemData;
+pub struct DrawSystemData {
+    /// The number of archetype batches this system was invoked for.
+    pub batches: usize,
+}
+
+#[derive(Debug, Default)]
+pub struct GravitySystemData;
+
+#[derive(Debug, Default)]
+pub struct WindSystemData;
+
+#[derive(Debug, Default)]
+pub struct DampSystemData;
+
+impl Default for AttractSystem {
+    fn default() -> Self {
+        Self(AttractSystemData::default())
+    }
+}
+
+impl Default for BounceSystem {
+    fn default() -> Self {
+        Self(BounceSystemData)
+    }
+}
 
 impl Default for StepSystem {
     fn default() -> Self {
-        Self(StepSystemData)
+        Self(StepSystemData::default())
+    }
+}
+
+impl Default for TallySystem {
+    fn default() -> Self {
+        Self(TallySystemData::default())
     }
 }
 
@@ -67,7 +122,25 @@ impl Default for HealSystem {
 
 impl Default for DrawSystem {
     fn default() -> Self {
-        Self(DrawSystemData)
+        Self(DrawSystemData::default())
+    }
+}
+
+impl Default for GravitySystem {
+    fn default() -> Self {
+        Self(GravitySystemData)
+    }
+}
+
+impl Default for WindSystem {
+    fn default() -> Self {
+        Self(WindSystemData)
+    }
+}
+
+impl Default for DampSystem {
+    fn default() -> Self {
+        Self(DampSystemData)
     }
 }
 
@@ -75,12 +148,30 @@ impl Default for DrawSystem {
 
 pub struct SystemFactory;
 
+impl CreateSystem<AttractSystem> for SystemFactory {
+    fn create(&self) -> AttractSystem {
+        AttractSystem::default()
+    }
+}
+
+impl CreateSystem<BounceSystem> for SystemFactory {
+    fn create(&self) -> BounceSystem {
+        BounceSystem::default()
+    }
+}
+
 impl CreateSystem<StepSystem> for SystemFactory {
     fn create(&self) -> StepSystem {
         StepSystem::default()
     }
 }
 
+impl CreateSystem<TallySystem> for SystemFactory {
+    fn create(&self) -> TallySystem {
+        TallySystem::default()
+    }
+}
+
 impl CreateSystem<HealSystem> for SystemFactory {
     fn create(&self) -> HealSystem {
         HealSystem::default()
@@ -93,14 +184,80 @@ impl CreateSystem<DrawSystem> for SystemFactory {
     }
 }
 
+impl CreateSystem<GravitySystem> for SystemFactory {
+    fn create(&self) -> GravitySystem {
+        GravitySystem::default()
+    }
+}
+
+impl CreateSystem<WindSystem> for SystemFactory {
+    fn create(&self) -> WindSystem {
+        WindSystem::default()
+    }
+}
+
+impl CreateSystem<DampSystem> for SystemFactory {
+    fn create(&self) -> DampSystem {
+        DampSystem::default()
+    }
+}
+
 // --- Apply<X>System impls -----------------------------------------------------
 //
 // The Apply traits provide defaults for every method, so the minimum a real
 // consumer must spell out is `type Error`. That's what we do here.
 
+// Iterates `LivingParticle` positions while mutating the positions of entities
+// in other archetypes through the lookup; both borrows coexist.
+impl ApplyAttractSystem for AttractSystem {
+    type Error = Infallible;
+
+    fn apply_single(
+        &mut self,
+        lookup_mut: &mut AttractComponentLookupMut<'_>,
+        _health: &HealthComponent,
+        position: &mut PositionComponent,
+    ) {
+        if let Some(probe) = self.probe {
+            self.probe_reachable |= lookup_mut.get_position_component(probe).is_some();
+        }
+        let Some(target) = self.target else {
+            return;
+        };
+        if let Some(target) = lookup_mut.get_position_component_mut(target) {
+            target.x += 1.0;
+            position.x = target.x;
+        }
+    }
+}
+
+// Mutates two outputs of the same entity at once through the typed item accessor.
+impl ApplyBounceSystem for BounceSystem {
+    type Error = Infallible;
+
+    fn apply_many(
+        &mut self,
+        entities: &[::sillyecs::EntityId],
+        positions: &mut [PositionComponent],
+        velocities: &mut [VelocityComponent],
+    ) {
+        for item in BounceSystem::iter_items(entities, positions, velocities) {
+            if item.position.y < 0.0 {
+                item.position.y = -item.position.y;
+                item.velocity.y = -item.velocity.y;
+            }
+        }
+    }
+}
+
 impl ApplyStepSystem for StepSystem {
     type Error = Infallible;
 
+    fn on_begin_phase(&mut self, _context: &::sillyecs::FrameContext) -> Result<(), Self::Error> {
+        self.runs += 1;
+        Ok(())
+    }
+
     fn preflight(
         &mut self,
         _context: &::sillyecs::FrameContext,
@@ -120,14 +277,65 @@ impl ApplyStepSystem for StepSystem {
     }
 }
 
+// Runs once per frame even though its phase is fixed-time.
+impl ApplyTallySystem for TallySystem {
+    type Error = Infallible;
+
+    fn on_begin_phase(&mut self, context: &::sillyecs::FrameContext) -> Result<(), Self::Error> {
+        self.runs += 1;
+        self.delta_time_secs = Some(context.delta_time_secs);
+        self.frame_number = Some(context.frame_number);
+        Ok(())
+    }
+}
+
 impl ApplyHealSystem for HealSystem {
     type Error = Infallible;
 }
 
 impl ApplyDrawSystem for DrawSystem {
     type Error = Infallible;
+
+    fn apply_many(
+        &mut self,
+        _renderer: &mut RendererState,
+        _entities: &[::sillyecs::EntityId],
+        _positions: &[PositionComponent],
+        _sprites: &[SpriteComponent],
+    ) {
+        self.batches += 1;
+    }
 }
 
+// Gravity and Wind both accumulate into the velocity; the order in which they run is irrelevant.
+impl ApplyGravitySystem for GravitySystem {
+    type Error = Infallible;
+
+    fn apply_single(&mut self, velocity: &mut VelocityComponent) {
+        velocity.y -= 1.0;
+    }
+}
+
+impl ApplyWindSystem for WindSystem {
+    type Error = Infallible;
+
+    fn apply_single(&mut self, velocity: &mut VelocityComponent) {
+        velocity.x += 1.0;
+    }
+}
+
+impl ApplyDampSystem for DampSystem {
+    type Error = Infallible;
+
+    fn apply_single(&mut self, velocity: &mut VelocityComponent) {
+        velocity.x *= 0.5;
+        velocity.y *= 0.5;
+    }
+}
+
+// Reports a missing system implementation at this single location.
+const _: () = assert_all_systems_implemented::<SystemFactory>();
+
 // --- User command + queue -----------------------------------------------------
 //
 // Issue #39 explicitly calls for a non-trivial `WorldCommandQueue` with a real
@@ -0,0 +1,72 @@
+// Runtime assertions for the `maybe` fixture. Included into the
+// generated fixture crate as a `#[cfg(test)]` module.
+
+fn spawn_all(world: &mut MainWorld<NoOpPhaseEvents, NoCommands>) -> [sillyecs::EntityId; 3] {
+    let racer = world.spawn(RacerEntityData {
+        position: PositionData { x: 0.0 },
+        velocity: VelocityData { x: 1.0 },
+        boost: BoostData { factor: 3.0 },
+    });
+    let drifter = world.spawn(DrifterEntityData {
+        position: PositionData { x: 0.0 },
+        velocity: VelocityData { x: 1.0 },
+    });
+    let rock = world.spawn(RockEntityData {
+        position: PositionData { x: 0.0 },
+    });
+    [racer, drifter, rock]
+}
+
+fn position_of(world: &MainWorld<NoOpPhaseEvents, NoCommands>, id: sillyecs::EntityId) -> f32 {
+    ComponentAccess::get_position_component(world, id).unwrap().x
+}
+
+#[test]
+fn maybe_components_do_not_restrict_the_archetypes() {
+    assert_eq!(DriveSystem::AFFECTED_ARCHETYPES, &["Drifter", "Racer"]);
+    assert_eq!(CensusSystem::AFFECTED_ARCHETYPES, &["Drifter", "Racer", "Rock"]);
+}
+
+#[test]
+fn maybe_components_are_present_only_in_archetypes_holding_them() {
+    let mut world: MainWorld<NoOpPhaseEvents, NoCommands> =
+        MainWorld::new(&SystemFactory, NoCommands);
+    let [racer, drifter, rock] = spawn_all(&mut world);
+
+    world.apply_system_phases();
+    assert_eq!(position_of(&world, racer), 3.0);
+    assert_eq!(position_of(&world, drifter), 1.0);
+    assert_eq!(position_of(&world, rock), 0.0);
+    assert_eq!(world.systems.as_drive_ref().boosted_archetypes, [false, true]);
+
+    let mut seen = world.systems.as_census_ref().seen.clone();
+    seen.sort_by_key(|entry| entry.0);
+    assert_eq!(
+        seen,
+        [(racer, true, true), (drifter, false, true), (rock, false, false)]
+    );
+}
+
+#[test]
+fn maybe_components_are_read_in_parallel() {
+    let mut world: MainWorld<NoOpPhaseEvents, NoCommands> =
+        MainWorld::new(&SystemFactory, NoCommands);
+    let [racer, drifter, _] = spawn_all(&mut world);
+
+    world.par_apply_system_phases();
+    assert_eq!(position_of(&world, racer), 3.0);
+    assert_eq!(position_of(&world, drifter), 1.0);
+    assert_eq!(world.systems.as_census_ref().seen.len(), 3);
+}
+
+#[test]
+fn archetypes_are_visited_in_the_declared_order() {
+    assert_eq!(SurveySystem::AFFECTED_ARCHETYPES, &["Rock", "Racer", "Drifter"]);
+
+    let mut world: MainWorld<NoOpPhaseEvents, NoCommands> =
+        MainWorld::new(&SystemFactory, NoCommands);
+    let [racer, drifter, rock] = spawn_all(&mut world);
+
+    world.apply_system_phases();
+    assert_eq!(world.systems.as_survey_ref().visited, [rock, racer, drifter]);
+}
@@ -0,0 +1,160 @@
+// Hand-written user-side stubs for the `maybe` compile fixture. Pairs
+// with `ecs.yaml` in this directory; included from the synthetic library
+// crate built by `tests/compile_generated.rs`.
+
+use std::collections::HashMap;
+use std::convert::Infallible;
+
+pub type EntityLocationMap<K, V> = HashMap<K, V>;
+
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct PositionData {
+    pub x: f32,
+}
+
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct VelocityData {
+    pub x: f32,
+}
+
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct BoostData {
+    pub factor: f32,
+}
+
+/// Moves every entity by its velocity, scaled by its boost if it has one.
+#[derive(Debug, Default)]
+pub struct DriveSystemData {
+    /// Whether each archetype visited by the preflight handed out boosts.
+    pub boosted_archetypes: Vec<bool>,
+}
+
+/// Counts the entities by the optional components they have.
+#[derive(Debug, Default)]
+pub struct CensusSystemData {
+    /// The entities visited, with whether they have a boost and a velocity.
+    pub seen: Vec<(sillyecs::EntityId, bool, bool)>,
+}
+
+/// Records the order in which entities are visited.
+#[derive(Debug, Default)]
+pub struct SurveySystemData {
+    /// The entities visited, in order.
+    pub visited: Vec<sillyecs::EntityId>,
+}
+
+impl Default for DriveSystem {
+    fn default() -> Self {
+        Self(DriveSystemData::default())
+    }
+}
+
+impl Default for CensusSystem {
+    fn default() -> Self {
+        Self(CensusSystemData::default())
+    }
+}
+
+impl Default for SurveySystem {
+    fn default() -> Self {
+        Self(SurveySystemData::default())
+    }
+}
+
+pub struct SystemFactory;
+
+impl CreateSystem<DriveSystem> for SystemFactory {
+    fn create(&self) -> DriveSystem {
+        DriveSystem::default()
+    }
+}
+
+impl CreateSystem<CensusSystem> for SystemFactory {
+    fn create(&self) -> CensusSystem {
+        CensusSystem::default()
+    }
+}
+
+impl CreateSystem<SurveySystem> for SystemFactory {
+    fn create(&self) -> SurveySystem {
+        SurveySystem::default()
+    }
+}
+
+impl ApplyDriveSystem for DriveSystem {
+    type Error = Infallible;
+
+    fn preflight(
+        &mut self,
+        _velocities: &[VelocityComponent],
+        boosts: Option<&[BoostComponent]>,
+        _positions: &[PositionComponent],
+    ) {
+        self.boosted_archetypes.push(boosts.is_some());
+    }
+
+    fn apply_many(
+        &mut self,
+        velocities: &[VelocityComponent],
+        boosts: Option<&[BoostComponent]>,
+        positions: &mut [PositionComponent],
+    ) {
+        for item in DriveSystem::iter_items(velocities, boosts, positions) {
+            let factor = item.boost.map_or(1.0, |boost| boost.factor);
+            item.position.x += item.velocity.x * factor;
+        }
+    }
+}
+
+impl ApplyCensusSystem for CensusSystem {
+    type Error = Infallible;
+
+    fn apply_single(
+        &mut self,
+        entity: sillyecs::EntityId,
+        boost: Option<&BoostComponent>,
+        velocity: Option<&VelocityComponent>,
+    ) {
+        self.seen.push((entity, boost.is_some(), velocity.is_some()));
+    }
+}
+
+impl ApplySurveySystem for SurveySystem {
+    type Error = Infallible;
+
+    fn apply_single(&mut self, entity: sillyecs::EntityId, _position: &PositionComponent) {
+        self.visited.push(entity);
+    }
+}
+
+const _: () = assert_all_systems_implemented::<SystemFactory>();
+
+#[derive(Debug, Default)]
+pub struct NoCommands;
+
+impl WorldUserCommand for NoCommands {
+    type UserCommand = ();
+}
+
+impl WorldCommandSender for NoCommands {
+    type Error = Infallible;
+
+    fn send(&self, _command: WorldCommand<Self::UserCommand>) -> Result<(), Self::Error> {
+        Ok(())
+    }
+}
+
+impl WorldCommandReceiver for NoCommands {
+    type Error = Infallible;
+
+    fn recv(&self) -> Result<Option<WorldCommand<Self::UserCommand>>, Self::Error> {
+        Ok(None)
+    }
+}
+
+impl<E, Q> WorldUserCommandHandler for MainWorld<E, Q>
+where
+    Q: WorldUserCommand<UserCommand = ()>,
+{
+    fn handle_user_command(&mut self, _command: Self::UserCommand) {}
+}
@@ -0,0 +1,58 @@
+// Runtime assertions for the `multi_world` fixture. Included into the
+// generated fixture crate as a `#[cfg(test)]` module.
+
+#[test]
+fn transfer_keeps_shared_components_under_a_fresh_id() {
+    let mut game: GameWorld<NoOpPhaseEvents, NoCommands> =
+        GameWorld::new(&SystemFactory, NoCommands);
+    let mut menu: MenuWorld<NoOpPhaseEvents, NoCommands> =
+        MenuWorld::new(&SystemFactory, NoCommands);
+
+    let first = game.spawn(PlayerEntityData {
+        position: PositionData { x: 1.0 },
+        velocity: VelocityData { x: 2.0 },
+        health: HealthData(3),
+    });
+    let second = game.spawn(PlayerEntityData {
+        position: PositionData { x: 4.0 },
+        velocity: VelocityData { x: 5.0 },
+        health: HealthData(6),
+    });
+
+    let transferred = game.transfer_to(first, &mut menu).unwrap();
+    assert_ne!(transferred, first);
+    assert_eq!(game.len(), 1);
+    assert_eq!(menu.len(), 1);
+    assert_eq!(game.validate_invariants(), Ok(()));
+    assert_eq!(menu.validate_invariants(), Ok(()));
+
+    let pawn = menu.get_pawn_entity(transferred).unwrap();
+    assert_eq!(pawn.position.x, 1.0);
+    assert_eq!(pawn.velocity.x, 2.0);
+    assert!(ComponentAccess::get_health_component(&menu, transferred).is_none());
+
+    // The entity moved into the hole of the transferred one stays addressable.
+    assert_eq!(game.get_player_entity(second).unwrap().position.x, 4.0);
+    assert!(matches!(
+        game.transfer_to(first, &mut menu),
+        Err(TransferError::EntityNotFound(id)) if id == first
+    ));
+}
+
+#[test]
+#[cfg(debug_assertions)]
+#[should_panic(expected = "but was used with the `Menu` world instance")]
+fn looking_up_an_entity_of_another_world_panics_in_debug() {
+    let mut game: GameWorld<NoOpPhaseEvents, NoCommands> =
+        GameWorld::new(&SystemFactory, NoCommands);
+    let menu: MenuWorld<NoOpPhaseEvents, NoCommands> =
+        MenuWorld::new(&SystemFactory, NoCommands);
+
+    let player = game.spawn(PlayerEntityData {
+        position: PositionData { x: 1.0 },
+        velocity: VelocityData { x: 2.0 },
+        health: HealthData(3),
+    });
+    assert!(player.world_tag().is_some());
+    let _ = menu.get_pawn_entity(player);
+}
@@ -0,0 +1,86 @@
+// Runtime assertions for the `neighbors` fixture. Included into the
+// generated fixture crate as a `#[cfg(test)]` module.
+
+fn spawn_boid(world: &mut MainWorld<NoOpPhaseEvents, NoCommands>, x: f32) -> sillyecs::EntityId {
+    world.spawn(BoidEntityData {
+        position: PositionData { x },
+        velocity: VelocityData::default(),
+    })
+}
+
+fn velocity_of(world: &MainWorld<NoOpPhaseEvents, NoCommands>, id: sillyecs::EntityId) -> f32 {
+    ComponentAccess::get_velocity_component(world, id).unwrap().x
+}
+
+#[test]
+fn boids_read_the_position_of_a_leading_boid() {
+    let mut world: MainWorld<NoOpPhaseEvents, NoCommands> =
+        MainWorld::new(&SystemFactory, NoCommands);
+    let leader = spawn_boid(&mut world, 10.0);
+    let follower = spawn_boid(&mut world, 4.0);
+    world.systems.as_flock_mut().leader = Some(leader);
+
+    world.apply_system_phases();
+    assert_eq!(velocity_of(&world, leader), 0.0);
+    assert_eq!(velocity_of(&world, follower), 6.0);
+}
+
+#[test]
+fn boids_read_neighbors_of_other_archetypes_in_parallel() {
+    let mut world: MainWorld<NoOpPhaseEvents, NoCommands> =
+        MainWorld::new(&SystemFactory, NoCommands);
+    let beacon = world.spawn(BeaconEntityData {
+        position: PositionData { x: -2.0 },
+    });
+    let boid = spawn_boid(&mut world, 1.0);
+    world.systems.as_flock_mut().leader = Some(beacon);
+
+    world.par_apply_system_phases();
+    assert_eq!(velocity_of(&world, boid), -3.0);
+}
+
+#[test]
+fn simple_world_alias_defaults_the_phase_events() {
+    let mut world: SimpleMainWorld<NoCommands> = SimpleMainWorld::new(&SystemFactory, NoCommands);
+    let boid = spawn_boid(&mut world, 1.0);
+    world.systems.as_flock_mut().leader = Some(boid);
+
+    world.apply_system_phases();
+    assert_eq!(velocity_of(&world, boid), 0.0);
+}
+
+fn spawn_beacon(
+    world: &mut MainWorld<NoOpPhaseEvents, NoCommands>,
+) -> Result<sillyecs::EntityId, SpawnError> {
+    world.try_spawn_beacon(BeaconEntityData {
+        position: PositionData::default(),
+    })
+}
+
+#[test]
+fn try_spawn_stops_at_the_maximum_number_of_entities() {
+    let mut world: MainWorld<NoOpPhaseEvents, NoCommands> =
+        MainWorld::new(&SystemFactory, NoCommands);
+    let first = spawn_beacon(&mut world).expect("below the maximum");
+    spawn_beacon(&mut world).expect("at the maximum");
+    assert_eq!(
+        spawn_beacon(&mut world),
+        Err(SpawnError::CapacityExceeded(BeaconArchetype::ID, 2))
+    );
+
+    world.despawn_by_id(first).unwrap();
+    assert!(spawn_beacon(&mut world).is_ok());
+}
+
+#[test]
+#[cfg(debug_assertions)]
+#[should_panic(expected = "exceeds the maximum of 2 `Beacon` entities")]
+fn spawn_past_the_maximum_panics_in_debug_builds() {
+    let mut world: MainWorld<NoOpPhaseEvents, NoCommands> =
+        MainWorld::new(&SystemFactory, NoCommands);
+    for _ in 0..3 {
+        let _ = world.spawn_beacon(BeaconEntityData {
+            position: PositionData::default(),
+        });
+    }
+}
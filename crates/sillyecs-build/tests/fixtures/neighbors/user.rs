@@ -0,0 +1,87 @@
+// Hand-written user-side stubs for the `neighbors` compile fixture. Pairs
+// with `ecs.yaml` in this directory; included from the synthetic library crate
+// built by `tests/compile_generated.rs`.
+
+use std::collections::HashMap;
+use std::convert::Infallible;
+
+pub type EntityLocationMap<K, V> = HashMap<K, V>;
+
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct PositionData {
+    pub x: f32,
+}
+
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct VelocityData {
+    pub x: f32,
+}
+
+/// Steers every boid towards its leader.
+#[derive(Debug, Default)]
+pub struct FlockSystemData {
+    /// The entity all boids follow; a boid or a beacon.
+    pub leader: Option<sillyecs::EntityId>,
+}
+
+impl Default for FlockSystem {
+    fn default() -> Self {
+        Self(FlockSystemData::default())
+    }
+}
+
+pub struct SystemFactory;
+
+impl CreateSystem<FlockSystem> for SystemFactory {
+    fn create(&self) -> FlockSystem {
+        FlockSystem::default()
+    }
+}
+
+impl ApplyFlockSystem for FlockSystem {
+    type Error = Infallible;
+
+    fn apply_single(
+        &mut self,
+        neighbors: &FlockNeighbors<'_>,
+        position: &PositionComponent,
+        velocity: &mut VelocityComponent,
+    ) {
+        let leader = self
+            .leader
+            .and_then(|leader| neighbors.get_position_component(leader));
+        velocity.x = leader.map_or(0.0, |leader| leader.x - position.x);
+    }
+}
+
+const _: () = assert_all_systems_implemented::<SystemFactory>();
+
+#[derive(Debug, Default)]
+pub struct NoCommands;
+
+impl WorldUserCommand for NoCommands {
+    type UserCommand = ();
+}
+
+impl WorldCommandSender for NoCommands {
+    type Error = Infallible;
+
+    fn send(&self, _command: WorldCommand<Self::UserCommand>) -> Result<(), Self::Error> {
+        Ok(())
+    }
+}
+
+impl WorldCommandReceiver for NoCommands {
+    type Error = Infallible;
+
+    fn recv(&self) -> Result<Option<WorldCommand<Self::UserCommand>>, Self::Error> {
+        Ok(None)
+    }
+}
+
+impl<E, Q> WorldUserCommandHandler for MainWorld<E, Q>
+where
+    Q: WorldUserCommand<UserCommand = ()>,
+{
+    fn handle_user_command(&mut self, _command: Self::UserCommand) {}
+}
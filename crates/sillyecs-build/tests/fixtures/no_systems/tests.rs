@@ -0,0 +1,24 @@
+// Runtime assertions for the `no_systems` fixture. Included into the
+// generated fixture crate as a `#[cfg(test)]` module.
+
+#[test]
+fn systemless_world_stores_entities_and_applies_phases() {
+    let mut world: MainWorld<NoOpPhaseEvents, NoCommands> =
+        MainWorld::new(&SystemFactory, NoCommands);
+    let id = world.spawn_marker(MarkerEntityComponents {
+        position: PositionComponent::new(PositionData { x: 1.0 }),
+    });
+
+    world.apply_system_phases();
+    world.par_apply_system_phases();
+
+    assert_eq!(world.len(), 1);
+    assert_eq!(ComponentAccess::get_position_component(&world, id).unwrap().x, 1.0);
+    assert!(world.systems_in_phase(SystemPhase::Update).is_empty());
+}
+
+#[test]
+fn systemless_world_has_a_default() {
+    let world: MainWorld<NoOpPhaseEvents, NoCommands> = MainWorld::default();
+    assert!(world.is_empty());
+}
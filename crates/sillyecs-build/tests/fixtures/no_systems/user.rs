@@ -0,0 +1,48 @@
+// Hand-written user-side stubs for the `no_systems` compile fixture. Pairs
+// with `ecs.yaml` in this directory; included from the synthetic library crate
+// built by `tests/compile_generated.rs`.
+
+use std::collections::HashMap;
+use std::convert::Infallible;
+
+pub type EntityLocationMap<K, V> = HashMap<K, V>;
+
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct PositionData {
+    pub x: f32,
+}
+
+/// Creates no systems, since the ECS declares none.
+pub struct SystemFactory;
+
+const _: () = assert_all_systems_implemented::<SystemFactory>();
+
+#[derive(Debug, Default)]
+pub struct NoCommands;
+
+impl WorldUserCommand for NoCommands {
+    type UserCommand = ();
+}
+
+impl WorldCommandSender for NoCommands {
+    type Error = Infallible;
+
+    fn send(&self, _command: WorldCommand<Self::UserCommand>) -> Result<(), Self::Error> {
+        Ok(())
+    }
+}
+
+impl WorldCommandReceiver for NoCommands {
+    type Error = Infallible;
+
+    fn recv(&self) -> Result<Option<WorldCommand<Self::UserCommand>>, Self::Error> {
+        Ok(None)
+    }
+}
+
+impl<E, Q> WorldUserCommandHandler for MainWorld<E, Q>
+where
+    Q: WorldUserCommand<UserCommand = ()>,
+{
+    fn handle_user_command(&mut self, _command: Self::UserCommand) {}
+}
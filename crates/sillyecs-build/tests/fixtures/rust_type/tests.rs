@@ -0,0 +1,29 @@
+// Runtime assertions for the `rust_type` fixture. Included into the
+// generated fixture crate as a `#[cfg(test)]` module.
+
+#[test]
+fn rust_type_components_are_stored_verbatim() {
+    assert_eq!(<Ring<4> as Component>::ID, ComponentId::Waveform);
+    assert_eq!(<Cow<'static, str> as Component>::ID, ComponentId::Label);
+
+    let mut world: MainWorld<NoOpPhaseEvents, NoCommands> =
+        MainWorld::new(&SystemFactory, NoCommands);
+    let components = SignalEntityComponents {
+        position: PositionComponent::new(PositionData::default()),
+        waveform: Buffer { samples: [0.0; 4] },
+        label: Cow::Borrowed("sine"),
+    };
+    let first = world.spawn_signal(components.clone());
+    let second = world.spawn(SignalEntityData {
+        position: PositionData::default(),
+        waveform: Buffer { samples: [1.0; 4] },
+        label: Cow::Owned(String::from("saw")),
+    });
+
+    world.apply_system_phases();
+
+    let first = ComponentAccess::get_waveform_component(&world, first).unwrap();
+    assert_eq!(first.samples, [4.0, 0.0, 0.0, 0.0]);
+    let second = ComponentAccess::get_waveform_component(&world, second).unwrap();
+    assert_eq!(second.samples, [3.0, 1.0, 1.0, 1.0]);
+}
@@ -0,0 +1,81 @@
+// Hand-written user-side stubs for the `rust_type` compile fixture. Pairs
+// with `ecs.yaml` in this directory; included from the synthetic library crate
+// built by `tests/compile_generated.rs`.
+
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::convert::Infallible;
+
+pub type EntityLocationMap<K, V> = HashMap<K, V>;
+
+// --- Component data structs ----------------------------------------------------
+
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct PositionData {
+    pub x: f32,
+}
+
+/// A fixed-size sample buffer; the generator cannot define it, only reference it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Buffer<const N: usize> {
+    pub samples: [f32; N],
+}
+
+/// A generic alias used as the `rust_type` of the `Waveform` component.
+pub type Ring<const N: usize> = Buffer<N>;
+
+// --- Systems ------------------------------------------------------------------
+
+#[derive(Debug, Default)]
+pub struct RecordSystemData;
+
+pub struct SystemFactory;
+
+impl CreateSystem<RecordSystem> for SystemFactory {
+    fn create(&self) -> RecordSystem {
+        RecordSystem(RecordSystemData)
+    }
+}
+
+impl ApplyRecordSystem for RecordSystem {
+    type Error = Infallible;
+
+    fn apply_single(&mut self, label: &Cow<'static, str>, waveform: &mut Ring<4>) {
+        waveform.samples.rotate_right(1);
+        waveform.samples[0] = label.len() as f32;
+    }
+}
+
+const _: () = assert_all_systems_implemented::<SystemFactory>();
+
+// --- Command queue ------------------------------------------------------------
+
+#[derive(Debug, Default)]
+pub struct NoCommands;
+
+impl WorldUserCommand for NoCommands {
+    type UserCommand = ();
+}
+
+impl WorldCommandSender for NoCommands {
+    type Error = Infallible;
+
+    fn send(&self, _command: WorldCommand<Self::UserCommand>) -> Result<(), Self::Error> {
+        Ok(())
+    }
+}
+
+impl WorldCommandReceiver for NoCommands {
+    type Error = Infallible;
+
+    fn recv(&self) -> Result<Option<WorldCommand<Self::UserCommand>>, Self::Error> {
+        Ok(None)
+    }
+}
+
+impl<E, Q> WorldUserCommandHandler for MainWorld<E, Q>
+where
+    Q: WorldUserCommand<UserCommand = ()>,
+{
+    fn handle_user_command(&mut self, _command: Self::UserCommand) {}
+}
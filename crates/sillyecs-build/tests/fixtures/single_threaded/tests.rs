@@ -0,0 +1,22 @@
+// Runtime assertions for the `single_threaded` fixture. Included into the
+// generated fixture crate as a `#[cfg(test)]` module.
+
+#[test]
+fn parallel_phase_runs_serially_with_non_send_data() {
+    let mut world: MainWorld<NoOpPhaseEvents, NoCommands> =
+        MainWorld::new(&SystemFactory, NoCommands);
+    let handle = Rc::new(Cell::new(0));
+    let _ = world.spawn_thing(ThingEntityComponents {
+        position: PositionComponent::new(PositionData::default()),
+        handle: HandleComponent::new(HandleData {
+            count: Rc::clone(&handle),
+        }),
+    });
+
+    world.apply_system_phases();
+    world.par_apply_system_phases();
+
+    assert_eq!(handle.get(), 2);
+    assert_eq!(world.systems.count.visited.get(), 2);
+    assert_eq!(world.archetypes.collection.thing.positions[0].x, 2.0);
+}
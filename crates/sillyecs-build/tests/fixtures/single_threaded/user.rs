@@ -0,0 +1,112 @@
+// Hand-written user-side stubs for the `single_threaded` compile fixture. Pairs
+// with `ecs.yaml` in this directory; included from the synthetic library crate
+// built by `tests/compile_generated.rs`.
+
+use std::cell::Cell;
+use std::collections::HashMap;
+use std::convert::Infallible;
+use std::rc::Rc;
+
+pub type EntityLocationMap<K, V> = HashMap<K, V>;
+
+// --- Component data structs ----------------------------------------------------
+
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct PositionData {
+    pub x: f32,
+}
+
+/// A shared handle, e.g. to a browser-side resource; `Rc` makes it `!Send + !Sync`.
+#[derive(Debug, Default, Clone)]
+pub struct HandleData {
+    pub count: Rc<Cell<u32>>,
+}
+
+// --- Systems ------------------------------------------------------------------
+
+/// Counts the handles it visited; the shared counter makes the system `!Send + !Sync`.
+#[derive(Debug, Default)]
+pub struct CountSystemData {
+    pub visited: Rc<Cell<usize>>,
+}
+
+#[derive(Debug, Default)]
+pub struct NudgeSystemData;
+
+impl Default for CountSystem {
+    fn default() -> Self {
+        Self(CountSystemData::default())
+    }
+}
+
+impl Default for NudgeSystem {
+    fn default() -> Self {
+        Self(NudgeSystemData)
+    }
+}
+
+pub struct SystemFactory;
+
+impl CreateSystem<CountSystem> for SystemFactory {
+    fn create(&self) -> CountSystem {
+        CountSystem::default()
+    }
+}
+
+impl CreateSystem<NudgeSystem> for SystemFactory {
+    fn create(&self) -> NudgeSystem {
+        NudgeSystem::default()
+    }
+}
+
+impl ApplyCountSystem for CountSystem {
+    type Error = Infallible;
+
+    fn apply_single(&mut self, handle: &HandleComponent) {
+        handle.count.set(handle.count.get() + 1);
+        self.visited.set(self.visited.get() + 1);
+    }
+}
+
+impl ApplyNudgeSystem for NudgeSystem {
+    type Error = Infallible;
+
+    fn apply_single(&mut self, position: &mut PositionComponent) {
+        position.x += 1.0;
+    }
+}
+
+const _: () = assert_all_systems_implemented::<SystemFactory>();
+
+// --- Command queue ------------------------------------------------------------
+
+/// Discards all commands; its user command is `!Send`.
+#[derive(Debug, Default)]
+pub struct NoCommands;
+
+impl WorldUserCommand for NoCommands {
+    type UserCommand = Rc<u32>;
+}
+
+impl WorldCommandSender for NoCommands {
+    type Error = Infallible;
+
+    fn send(&self, _command: WorldCommand<Self::UserCommand>) -> Result<(), Self::Error> {
+        Ok(())
+    }
+}
+
+impl WorldCommandReceiver for NoCommands {
+    type Error = Infallible;
+
+    fn recv(&self) -> Result<Option<WorldCommand<Self::UserCommand>>, Self::Error> {
+        Ok(None)
+    }
+}
+
+impl<E, Q> WorldUserCommandHandler for MainWorld<E, Q>
+where
+    Q: WorldUserCommand<UserCommand = Rc<u32>>,
+{
+    fn handle_user_command(&mut self, _command: Self::UserCommand) {}
+}
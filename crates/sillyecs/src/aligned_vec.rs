@@ -0,0 +1,279 @@
+use crate::ComponentStorage;
+use std::alloc::{self, Layout};
+use std::fmt::{Debug, Formatter};
+use std::marker::PhantomData;
+use std::ops::{Deref, DerefMut};
+use std::ptr::{self, NonNull};
+
+/// A growable, contiguous storage whose data is aligned to at least `A` bytes.
+///
+/// Generated archetypes store components declaring an `align` in this type instead of the
+/// configured storage, e.g. to load them with aligned SIMD instructions. Elements are packed
+/// as in a [`Vec`]; only the start of the data is aligned, to the larger of `A` and the
+/// alignment of `T`.
+pub struct AlignedVec<T, const A: usize> {
+    ptr: NonNull<T>,
+    len: usize,
+    cap: usize,
+    _marker: PhantomData<T>,
+}
+
+// SAFETY: The storage owns its values like a `Vec` does.
+unsafe impl<T: Send, const A: usize> Send for AlignedVec<T, A> {}
+
+// SAFETY: The storage only hands out shared references to its values through `&self`.
+unsafe impl<T: Sync, const A: usize> Sync for AlignedVec<T, A> {}
+
+impl<T, const A: usize> AlignedVec<T, A> {
+    /// The alignment of the data.
+    pub const ALIGN: usize = {
+        assert!(A.is_power_of_two(), "the alignment must be a power of two");
+        if A > align_of::<T>() {
+            A
+        } else {
+            align_of::<T>()
+        }
+    };
+
+    /// Whether `T` is zero-sized and thus never allocates.
+    const IS_ZST: bool = size_of::<T>() == 0;
+
+    /// Creates an empty storage without allocating.
+    pub const fn new() -> Self {
+        Self {
+            ptr: NonNull::new(ptr::without_provenance_mut(Self::ALIGN))
+                .expect("the alignment is not zero"),
+            len: 0,
+            cap: if Self::IS_ZST { usize::MAX } else { 0 },
+            _marker: PhantomData,
+        }
+    }
+
+    /// Creates an empty storage with room for at least `capacity` values.
+    pub fn with_capacity(capacity: usize) -> Self {
+        let mut vec = Self::new();
+        vec.reserve(capacity);
+        vec
+    }
+
+    /// Returns a raw pointer to the aligned data.
+    #[inline]
+    pub const fn as_ptr(&self) -> *const T {
+        self.ptr.as_ptr()
+    }
+
+    /// Appends a value to the end of the storage.
+    #[inline]
+    pub fn push(&mut self, value: T) {
+        if self.len == self.cap {
+            self.reserve(1);
+        }
+        // SAFETY: The slot at `len` is within the capacity and not initialized.
+        unsafe { self.ptr.as_ptr().add(self.len).write(value) }
+        self.len += 1;
+    }
+
+    /// Removes the value at `index` and returns it, replacing it with the last value.
+    ///
+    /// # Panics
+    /// Panics if `index` is out of bounds.
+    pub fn swap_remove(&mut self, index: usize) -> T {
+        let len = self.len;
+        assert!(
+            index < len,
+            "swap_remove index (is {index}) should be < len (is {len})"
+        );
+        // SAFETY: Both indexes are in bounds. The value at `index` is moved out, and the last
+        // value is moved into its slot before the length shrinks past it.
+        unsafe {
+            let base = self.ptr.as_ptr();
+            let value = base.add(index).read();
+            ptr::copy(base.add(len - 1), base.add(index), 1);
+            self.len = len - 1;
+            value
+        }
+    }
+
+    /// Reserves capacity for at least `additional` more values.
+    pub fn reserve(&mut self, additional: usize) {
+        let required = self.len.checked_add(additional).expect("capacity overflow");
+        if required > self.cap {
+            let capacity = required.max(self.cap * 2).max(4);
+            self.reallocate(capacity);
+        }
+    }
+
+    /// Shrinks the capacity of the storage as much as possible.
+    pub fn shrink_to_fit(&mut self) {
+        if !Self::IS_ZST && self.cap > self.len {
+            self.reallocate(self.len);
+        }
+    }
+
+    /// Returns the number of values the storage can hold without reallocating.
+    #[inline]
+    pub const fn capacity(&self) -> usize {
+        self.cap
+    }
+
+    fn layout(capacity: usize) -> Layout {
+        size_of::<T>()
+            .checked_mul(capacity)
+            .and_then(|size| Layout::from_size_align(size, Self::ALIGN).ok())
+            .expect("capacity overflow")
+    }
+
+    /// Moves the values into an allocation holding exactly `capacity` values.
+    fn reallocate(&mut self, capacity: usize) {
+        debug_assert!(!Self::IS_ZST && capacity >= self.len);
+        if capacity == 0 {
+            self.deallocate();
+            self.ptr = Self::new().ptr;
+            self.cap = 0;
+            return;
+        }
+
+        let layout = Self::layout(capacity);
+        // SAFETY: The layout has a non-zero size since `T` is not zero-sized. A non-zero capacity
+        // was allocated with the layout of that capacity, whose alignment `realloc` keeps.
+        let ptr = unsafe {
+            if self.cap == 0 {
+                alloc::alloc(layout)
+            } else {
+                alloc::realloc(
+                    self.ptr.as_ptr().cast(),
+                    Self::layout(self.cap),
+                    layout.size(),
+                )
+            }
+        };
+        self.ptr = NonNull::new(ptr.cast()).unwrap_or_else(|| alloc::handle_alloc_error(layout));
+        self.cap = capacity;
+    }
+
+    fn deallocate(&mut self) {
+        if !Self::IS_ZST && self.cap > 0 {
+            // SAFETY: The data was allocated with the layout of the current capacity.
+            unsafe { alloc::dealloc(self.ptr.as_ptr().cast(), Self::layout(self.cap)) }
+        }
+    }
+}
+
+impl<T, const A: usize> Drop for AlignedVec<T, A> {
+    fn drop(&mut self) {
+        // SAFETY: The first `len` values are initialized and dropped exactly once.
+        unsafe { ptr::drop_in_place(ptr::slice_from_raw_parts_mut(self.ptr.as_ptr(), self.len)) }
+        self.deallocate();
+    }
+}
+
+impl<T, const A: usize> Default for AlignedVec<T, A> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: Clone, const A: usize> Clone for AlignedVec<T, A> {
+    fn clone(&self) -> Self {
+        let mut vec = Self::with_capacity(self.len);
+        for value in self.iter() {
+            vec.push(value.clone());
+        }
+        vec
+    }
+}
+
+impl<T: Debug, const A: usize> Debug for AlignedVec<T, A> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
+impl<T, const A: usize> Deref for AlignedVec<T, A> {
+    type Target = [T];
+
+    #[inline]
+    fn deref(&self) -> &[T] {
+        // SAFETY: The pointer is aligned and non-null, and the first `len` values are initialized.
+        unsafe { std::slice::from_raw_parts(self.ptr.as_ptr(), self.len) }
+    }
+}
+
+impl<T, const A: usize> DerefMut for AlignedVec<T, A> {
+    #[inline]
+    fn deref_mut(&mut self) -> &mut [T] {
+        // SAFETY: As for `deref`, and `&mut self` guarantees exclusive access.
+        unsafe { std::slice::from_raw_parts_mut(self.ptr.as_ptr(), self.len) }
+    }
+}
+
+impl<T, const A: usize> ComponentStorage<T> for AlignedVec<T, A> {
+    #[inline]
+    fn push(&mut self, value: T) {
+        self.push(value);
+    }
+
+    #[inline]
+    fn swap_remove(&mut self, index: usize) -> T {
+        self.swap_remove(index)
+    }
+
+    #[inline]
+    fn reserve(&mut self, additional: usize) {
+        self.reserve(additional);
+    }
+
+    #[inline]
+    fn shrink_to_fit(&mut self) {
+        self.shrink_to_fit();
+    }
+
+    #[inline]
+    fn capacity(&self) -> usize {
+        self.capacity()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_data_is_aligned() {
+        let mut vec = AlignedVec::<u8, 64>::new();
+        for value in 0..100 {
+            vec.push(value);
+            assert_eq!(vec.as_ptr() as usize % 64, 0);
+        }
+        assert_eq!(vec.len(), 100);
+        assert_eq!(vec.swap_remove(0), 0);
+        assert_eq!(vec[0], 99);
+
+        vec.shrink_to_fit();
+        assert_eq!(vec.capacity(), 99);
+        assert_eq!(vec.as_ptr() as usize % 64, 0);
+        assert_eq!(*vec.clone(), *vec);
+    }
+
+    #[test]
+    fn test_values_are_dropped() {
+        let value = std::rc::Rc::new(());
+        let mut vec = AlignedVec::<_, 32>::with_capacity(2);
+        vec.push(value.clone());
+        vec.push(value.clone());
+        drop(vec.pop());
+        assert_eq!(std::rc::Rc::strong_count(&value), 2);
+        drop(vec);
+        assert_eq!(std::rc::Rc::strong_count(&value), 1);
+    }
+
+    #[test]
+    fn test_zero_sized_values() {
+        let mut vec = AlignedVec::<(), 16>::new();
+        vec.push(());
+        vec.push(());
+        assert_eq!(vec.len(), 2);
+        vec.shrink_to_fit();
+        assert_eq!(vec.pop(), Some(()));
+    }
+}
@@ -1,40 +1,229 @@
+use crate::WorldTag;
 use core::num::NonZeroU64;
-use core::sync::atomic::AtomicU64;
+
+#[cfg(not(feature = "entity-id-u32"))]
+type Repr = NonZeroU64;
+#[cfg(not(feature = "entity-id-u32"))]
+type RawRepr = u64;
+#[cfg(not(feature = "entity-id-u32"))]
+type AtomicRepr = core::sync::atomic::AtomicU64;
+
+#[cfg(feature = "entity-id-u32")]
+type Repr = core::num::NonZeroU32;
+#[cfg(feature = "entity-id-u32")]
+type RawRepr = u32;
+#[cfg(feature = "entity-id-u32")]
+type AtomicRepr = core::sync::atomic::AtomicU32;
 
 /// The ID of an entity.
-#[derive(Debug, Copy, Clone, Ord, PartialOrd, Eq, PartialEq)]
-pub struct EntityId(NonZeroU64);
+///
+/// Backed by a [`NonZeroU64`] by default. With the `entity-id-u32` feature enabled, the ID is
+/// backed by a [`NonZeroU32`](core::num::NonZeroU32) instead, halving its size; the `u64`
+/// conversions remain available and widen the value.
+///
+/// With the `world-tag` feature enabled, IDs handed out by a world additionally carry the
+/// [`WorldTag`] of that world instance in debug builds, see [`EntityId::world_tag`]. The tag takes
+/// no part in comparisons or hashing, but doubles the size of the ID.
+#[derive(Copy, Clone)]
+pub struct EntityId {
+    id: Repr,
+    #[cfg(all(feature = "world-tag", debug_assertions))]
+    world: Option<WorldTag>,
+}
 
 #[allow(dead_code)]
 impl EntityId {
     /// Returns a new, unique entity ID.
     ///
-    /// Uniqueness is guaranteed by using a monotonically increasing `AtomicU64` counter
+    /// Uniqueness is guaranteed by using a monotonically increasing atomic counter
     /// for generating IDs, starting from 1.
     ///
+    /// # Panics
+    /// Panics once the entity ID space is exhausted, i.e. after the largest ID was handed out;
+    /// see [`EntityId::try_new`](EntityId::try_new) for a non-panicking variant.
+    ///
     /// # Implementation
     /// This function uses a thread-safe counter with sequential consistency ordering
     /// to ensure unique IDs even under concurrent access.
+    #[allow(clippy::new_without_default)]
     pub fn new() -> Self {
-        static ENTITY_IDS: AtomicU64 = AtomicU64::new(1);
-        let id = ENTITY_IDS.fetch_add(1, core::sync::atomic::Ordering::SeqCst);
-        EntityId(NonZeroU64::new(id).expect("ID was zero"))
+        Self::try_new().expect("the entity ID space is exhausted")
+    }
+
+    /// Returns a new, unique entity ID, or `None` once the entity ID space is exhausted.
+    ///
+    /// Unlike a plain wrapping counter, the allocator never starts over at 1 after handing out
+    /// the largest ID, so IDs are never reused.
+    pub fn try_new() -> Option<Self> {
+        static ENTITY_IDS: AtomicRepr = AtomicRepr::new(1);
+        Self::allocate(&ENTITY_IDS)
+    }
+
+    /// Takes the next ID from `counter`, which holds the next ID to hand out, or zero once the
+    /// largest ID was handed out.
+    fn allocate(counter: &AtomicRepr) -> Option<Self> {
+        use core::sync::atomic::Ordering;
+
+        counter
+            .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |id| {
+                (id != 0).then(|| id.wrapping_add(1))
+            })
+            .ok()
+            .and_then(Repr::new)
+            .map(Self::from_repr)
+    }
+
+    /// Creates an entity ID from a raw `u64` value, returning `None` if the value is zero
+    /// (or, with the `entity-id-u32` feature, does not fit into a `u32`).
+    ///
+    /// This is intended for IDs handed out by an external allocator or received over FFI.
+    /// No uniqueness check is performed; the caller is responsible for not creating IDs that
+    /// collide with ones obtained from [`EntityId::new`](EntityId::new).
+    // The casts are no-ops unless the `entity-id-u32` feature is enabled.
+    #[allow(clippy::unnecessary_cast)]
+    pub const fn new_checked(id: u64) -> Option<Self> {
+        if id > RawRepr::MAX as u64 {
+            return None;
+        }
+        match Repr::new(id as RawRepr) {
+            Some(id) => Some(Self::from_repr(id)),
+            None => None,
+        }
+    }
+
+    /// Creates an entity ID from a raw `u64` value without checking that it is nonzero.
+    ///
+    /// The zero check is only performed as a debug assertion.
+    ///
+    /// # Safety
+    /// The value must not be zero. With the `entity-id-u32` feature, the value must
+    /// additionally fit into a `u32`; this is likewise only checked as a debug assertion.
+    /// As with [`EntityId::new_checked`](EntityId::new_checked), the caller is additionally
+    /// responsible for ensuring uniqueness of the resulting ID.
+    // The casts are no-ops unless the `entity-id-u32` feature is enabled.
+    #[allow(clippy::unnecessary_cast)]
+    pub const unsafe fn new_unchecked(id: u64) -> Self {
+        debug_assert!(id != 0, "entity ID must not be zero");
+        debug_assert!(id <= RawRepr::MAX as u64, "entity ID out of range");
+        // SAFETY: The caller guarantees that `id` is nonzero and in range.
+        Self::from_repr(unsafe { Repr::new_unchecked(id as RawRepr) })
+    }
+
+    /// Creates an entity ID that belongs to no world.
+    const fn from_repr(id: Repr) -> Self {
+        Self {
+            id,
+            #[cfg(all(feature = "world-tag", debug_assertions))]
+            world: None,
+        }
+    }
+
+    /// Returns the world instance this entity was spawned in.
+    ///
+    /// World tags are only tracked in debug builds with the `world-tag` feature enabled; otherwise,
+    /// and for IDs that were not spawned by a world (e.g. ones created by [`EntityId::new_checked`]), this returns `None`.
+    pub const fn world_tag(&self) -> Option<WorldTag> {
+        #[cfg(all(feature = "world-tag", debug_assertions))]
+        {
+            self.world
+        }
+        #[cfg(not(all(feature = "world-tag", debug_assertions)))]
+        {
+            None
+        }
+    }
+
+    /// Returns this ID tagged with the world instance it is spawned in. Called by generated worlds.
+    #[doc(hidden)]
+    #[inline]
+    #[allow(unused_variables)]
+    pub const fn with_world_tag(self, world: WorldTag) -> Self {
+        Self {
+            id: self.id,
+            #[cfg(all(feature = "world-tag", debug_assertions))]
+            world: Some(world),
+        }
+    }
+
+    /// Panics in debug builds with the `world-tag` feature enabled if this entity was spawned in a
+    /// world instance other than `world`, which is named `name`. Called by generated worlds when an
+    /// entity ID is unknown to them.
+    #[doc(hidden)]
+    #[inline]
+    #[track_caller]
+    #[allow(unused_variables)]
+    pub fn debug_assert_world(&self, world: WorldTag, name: &str) {
+        #[cfg(all(feature = "world-tag", debug_assertions))]
+        if let Some(tag) = self.world {
+            assert!(
+                tag == world,
+                "Entity {self} belongs to the world instance {tag}, but was used with the `{name}` world instance {world}",
+                tag = tag.0,
+                world = world.0
+            );
+        }
     }
 
     /// Returns this ID as a [`NonZeroU64`](NonZeroU64) value.
     pub const fn as_nonzero_u64(&self) -> NonZeroU64 {
-        self.0
+        match NonZeroU64::new(self.as_u64()) {
+            Some(id) => id,
+            None => unreachable!(),
+        }
     }
 
     /// Returns this ID as a `u64` value.
+    // The casts are no-ops unless the `entity-id-u32` feature is enabled.
+    #[allow(clippy::unnecessary_cast)]
     pub const fn as_u64(&self) -> u64 {
-        self.0.get()
+        self.id.get() as u64
+    }
+
+    /// Returns this ID as a [`NonZeroU32`](core::num::NonZeroU32) value.
+    #[cfg(feature = "entity-id-u32")]
+    pub const fn as_nonzero_u32(&self) -> core::num::NonZeroU32 {
+        self.id
+    }
+
+    /// Returns this ID as a `u32` value.
+    #[cfg(feature = "entity-id-u32")]
+    pub const fn as_u32(&self) -> u32 {
+        self.id.get()
+    }
+}
+
+impl PartialEq for EntityId {
+    #[inline]
+    fn eq(&self, other: &Self) -> bool {
+        self.id == other.id
+    }
+}
+
+impl Eq for EntityId {}
+
+impl PartialOrd for EntityId {
+    #[inline]
+    fn partial_cmp(&self, other: &Self) -> Option<core::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for EntityId {
+    #[inline]
+    fn cmp(&self, other: &Self) -> core::cmp::Ordering {
+        self.id.cmp(&other.id)
     }
 }
 
 impl core::hash::Hash for EntityId {
     fn hash<H: core::hash::Hasher>(&self, state: &mut H) {
-        self.0.hash(state);
+        self.id.hash(state);
+    }
+}
+
+impl core::fmt::Debug for EntityId {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_tuple("EntityId").field(&self.id).finish()
     }
 }
 
@@ -50,8 +239,122 @@ impl From<EntityId> for u64 {
     }
 }
 
+#[cfg(feature = "entity-id-u32")]
+impl From<EntityId> for core::num::NonZeroU32 {
+    fn from(value: EntityId) -> core::num::NonZeroU32 {
+        value.as_nonzero_u32()
+    }
+}
+
+#[cfg(feature = "entity-id-u32")]
+impl From<EntityId> for u32 {
+    fn from(value: EntityId) -> u32 {
+        value.as_u32()
+    }
+}
+
 impl core::fmt::Display for EntityId {
     fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> Result<(), core::fmt::Error> {
-        core::fmt::Display::fmt(&self.0.get(), f)
+        core::fmt::Display::fmt(&self.id.get(), f)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_new_checked() {
+        let id = EntityId::new_checked(42).expect("nonzero ID was rejected");
+        assert_eq!(id.as_u64(), 42);
+        assert_eq!(EntityId::new_checked(0), None);
+    }
+
+    #[test]
+    fn test_new_unchecked() {
+        let id = unsafe { EntityId::new_unchecked(7) };
+        assert_eq!(id.as_u64(), 7);
+        assert_eq!(Some(id), EntityId::new_checked(7));
+    }
+
+    #[test]
+    fn test_try_new_is_unique() {
+        let first = EntityId::try_new().expect("ID space is not exhausted");
+        let second = EntityId::new();
+        assert_ne!(first, second);
+    }
+
+    #[test]
+    // The casts are no-ops unless the `entity-id-u32` feature is enabled.
+    #[allow(clippy::unnecessary_cast)]
+    fn test_allocation_stops_at_exhaustion() {
+        let counter = AtomicRepr::new(RawRepr::MAX - 1);
+        let ids: Vec<_> = (0..4).map(|_| EntityId::allocate(&counter)).collect();
+        assert_eq!(
+            ids,
+            [
+                EntityId::new_checked((RawRepr::MAX - 1) as u64),
+                EntityId::new_checked(RawRepr::MAX as u64),
+                None,
+                None,
+            ]
+        );
+    }
+
+    #[test]
+    fn test_size() {
+        #[cfg(all(
+            not(feature = "entity-id-u32"),
+            not(all(feature = "world-tag", debug_assertions))
+        ))]
+        const EXPECTED: usize = 8;
+        #[cfg(all(
+            feature = "entity-id-u32",
+            not(all(feature = "world-tag", debug_assertions))
+        ))]
+        const EXPECTED: usize = 4;
+        // Debug builds with the `world-tag` feature additionally carry the world tag.
+        #[cfg(all(
+            not(feature = "entity-id-u32"),
+            feature = "world-tag",
+            debug_assertions
+        ))]
+        const EXPECTED: usize = 16;
+        #[cfg(all(feature = "entity-id-u32", feature = "world-tag", debug_assertions))]
+        const EXPECTED: usize = 8;
+
+        assert_eq!(size_of::<EntityId>(), EXPECTED);
+        assert_eq!(size_of::<Option<EntityId>>(), EXPECTED);
+        assert_eq!(size_of::<crate::WorldId>(), 8);
+    }
+
+    #[test]
+    fn test_conversions() {
+        let id = EntityId::new_checked(1234).expect("nonzero ID was rejected");
+        assert_eq!(u64::from(id), 1234);
+        assert_eq!(NonZeroU64::from(id).get(), 1234);
+        assert_eq!(id.to_string(), "1234");
+
+        #[cfg(feature = "entity-id-u32")]
+        {
+            assert_eq!(u32::from(id), 1234);
+            assert_eq!(core::num::NonZeroU32::from(id).get(), 1234);
+        }
+    }
+
+    #[test]
+    fn test_new_checked_range() {
+        let id = EntityId::new_checked(u64::from(u32::MAX) + 1);
+        #[cfg(not(feature = "entity-id-u32"))]
+        assert_eq!(id.map(|id| id.as_u64()), Some(u64::from(u32::MAX) + 1));
+        #[cfg(feature = "entity-id-u32")]
+        assert_eq!(id, None);
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic(expected = "entity ID must not be zero")]
+    fn test_new_unchecked_zero_panics_in_debug() {
+        let _ = unsafe { EntityId::new_unchecked(0) };
     }
 }
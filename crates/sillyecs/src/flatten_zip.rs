@@ -0,0 +1,113 @@
+use std::borrow::Cow;
+use std::iter::FusedIterator;
+
+/// An iterator over two equally shaped slices of slices.
+///
+/// Presents the inner slices of both sets as one contiguous set of pairs, e.g. to read two
+/// different components of the same entities across archetypes with a single cursor.
+#[derive(Debug)]
+pub struct FlattenZip<'a, A, B> {
+    a: Cow<'a, [&'a [A]]>,
+    b: Cow<'a, [&'a [B]]>,
+    front: (usize, usize), // (slice index, element index)
+}
+
+impl<'a, A, B> FlattenZip<'a, A, B> {
+    /// Creates the iterator.
+    ///
+    /// The inner slices at the same position are expected to have the same length; in debug
+    /// builds, a mismatch panics. In release builds, each pair of inner slices is iterated up to
+    /// the end of the shorter one.
+    pub fn new<const N: usize>(a: [&'a [A]; N], b: [&'a [B]; N]) -> Self {
+        debug_assert!(
+            a.iter().zip(b.iter()).all(|(a, b)| a.len() == b.len()),
+            "zipped slices must have equal lengths"
+        );
+        Self {
+            a: Cow::Owned(a.into()),
+            b: Cow::Owned(b.into()),
+            front: (0, 0),
+        }
+    }
+
+    pub fn reset(&mut self) {
+        self.front = (0, 0);
+    }
+
+    /// The number of pairs in the inner slices at the specified index.
+    #[inline]
+    fn pairs_in(&self, slice_idx: usize) -> usize {
+        self.a[slice_idx].len().min(self.b[slice_idx].len())
+    }
+}
+
+impl<'a, A, B> Iterator for FlattenZip<'a, A, B> {
+    type Item = (&'a A, &'a B);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while self.front.0 < self.a.len() {
+            let (slice_idx, elem_idx) = self.front;
+            let len = self.pairs_in(slice_idx);
+
+            if elem_idx < len {
+                self.front.1 += 1;
+
+                if self.front.1 >= len {
+                    self.front.0 += 1;
+                    self.front.1 = 0;
+                }
+
+                return Some((&self.a[slice_idx][elem_idx], &self.b[slice_idx][elem_idx]));
+            }
+
+            self.front.0 += 1;
+            self.front.1 = 0;
+        }
+
+        None
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let mut count = 0;
+        for i in self.front.0..self.a.len() {
+            let start = if i == self.front.0 { self.front.1 } else { 0 };
+            count += self.pairs_in(i).saturating_sub(start);
+        }
+        (count, Some(count))
+    }
+}
+
+impl<'a, A, B> ExactSizeIterator for FlattenZip<'a, A, B> {}
+impl<'a, A, B> FusedIterator for FlattenZip<'a, A, B> {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_forward() {
+        let a1 = &[1, 2][..];
+        let a2 = &[][..];
+        let a3 = &[3][..];
+        let b1 = &['a', 'b'][..];
+        let b2 = &[][..];
+        let b3 = &['c'][..];
+
+        let mut iter = FlattenZip::new([a1, a2, a3], [b1, b2, b3]);
+        assert_eq!(iter.size_hint(), (3, Some(3)));
+        assert_eq!(iter.next(), Some((&1, &'a')));
+        assert_eq!(iter.len(), 2);
+
+        let pairs: Vec<_> = iter.map(|(&a, &b)| (a, b)).collect();
+        assert_eq!(pairs, [(2, 'b'), (3, 'c')]);
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic(expected = "zipped slices must have equal lengths")]
+    fn test_mismatched_shape() {
+        let a = &[1, 2][..];
+        let b = &['a'][..];
+        let _ = FlattenZip::new([a], [b]);
+    }
+}
@@ -16,6 +16,8 @@ pub struct FrameContext {
     pub current_frame_start: std::time::Instant,
     /// The start time of the last frame.
     pub last_frame_start: std::time::Instant,
+    /// The seed for deterministic per-frame random numbers, e.g. for lockstep simulations and replays.
+    pub rng_seed: u64,
 }
 
 #[allow(dead_code)]
@@ -30,6 +32,7 @@ impl FrameContext {
             fixed_time_secs: 1.0 / 60.0,
             current_frame_start: std::time::Instant::now(),
             last_frame_start: std::time::Instant::now(),
+            rng_seed: 0,
         }
     }
 
@@ -39,4 +42,167 @@ impl FrameContext {
         self.current_frame_start = std::time::Instant::now();
         self.last_frame_start = std::time::Instant::now();
     }
+
+    /// Returns a builder for a frame context with explicit timing, e.g. to test time-dependent
+    /// systems reproducibly.
+    #[doc(hidden)]
+    pub fn builder(world_id: WorldId) -> FrameContextBuilder {
+        FrameContextBuilder::new(world_id)
+    }
+
+    /// Returns a random number generator for the current frame.
+    ///
+    /// The generator is derived from the world ID, the frame number and [`rng_seed`](Self::rng_seed)
+    /// only, so runs with the same seed observe the same sequences frame by frame. It is not suited
+    /// for cryptographic purposes.
+    #[cfg(feature = "rand")]
+    pub fn frame_rng(&self) -> impl rand::Rng + use<> {
+        use rand::SeedableRng;
+
+        let seed = [self.world_id.as_u64(), self.frame_number]
+            .into_iter()
+            .fold(self.rng_seed, |state, value| splitmix64(state ^ value));
+        rand::rngs::Xoshiro256PlusPlus::seed_from_u64(seed)
+    }
+}
+
+/// Builds a [`FrameContext`] with explicit frame number, timing and frame-start instants.
+///
+/// [`FrameContext::new`] starts at frame zero and samples [`Instant::now`](std::time::Instant::now),
+/// which makes tests of time-dependent systems awkward. Unless set explicitly, the current frame
+/// starts now and the last frame started [`delta_time_secs`](Self::delta_time_secs) earlier.
+#[doc(hidden)]
+#[derive(Debug, Clone)]
+pub struct FrameContextBuilder {
+    context: FrameContext,
+    frame_starts: Option<(std::time::Instant, std::time::Instant)>,
+}
+
+#[allow(dead_code)]
+impl FrameContextBuilder {
+    /// Creates a builder starting from the defaults of [`FrameContext::new`].
+    pub fn new(world_id: WorldId) -> Self {
+        Self {
+            context: FrameContext::new(world_id),
+            frame_starts: None,
+        }
+    }
+
+    /// Sets the frame number.
+    pub fn frame_number(mut self, frame_number: u64) -> Self {
+        self.context.frame_number = frame_number;
+        self
+    }
+
+    /// Sets the delta time since the last frame.
+    pub fn delta_time_secs(mut self, delta_time_secs: f32) -> Self {
+        self.context.delta_time_secs = delta_time_secs;
+        self
+    }
+
+    /// Sets the fixed time for fixed-time systems.
+    pub fn fixed_time_secs(mut self, fixed_time_secs: f32) -> Self {
+        self.context.fixed_time_secs = fixed_time_secs;
+        self
+    }
+
+    /// Sets the start times of the last and the current frame.
+    pub fn frame_starts(mut self, last: std::time::Instant, current: std::time::Instant) -> Self {
+        self.frame_starts = Some((last, current));
+        self
+    }
+
+    /// Sets the seed for deterministic per-frame random numbers.
+    pub fn rng_seed(mut self, rng_seed: u64) -> Self {
+        self.context.rng_seed = rng_seed;
+        self
+    }
+
+    /// Builds the frame context.
+    pub fn build(self) -> FrameContext {
+        let mut context = self.context;
+        let (last, current) = self.frame_starts.unwrap_or_else(|| {
+            let current = context.current_frame_start;
+            let delta =
+                std::time::Duration::try_from_secs_f32(context.delta_time_secs).unwrap_or_default();
+            (current.checked_sub(delta).unwrap_or(current), current)
+        });
+        context.last_frame_start = last;
+        context.current_frame_start = current;
+        context
+    }
+}
+
+/// A single SplitMix64 step, used to mix the inputs of [`FrameContext::frame_rng`].
+#[cfg(feature = "rand")]
+const fn splitmix64(state: u64) -> u64 {
+    let mut z = state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    z ^ (z >> 31)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use core::num::NonZeroU64;
+    #[cfg(feature = "rand")]
+    use rand::Rng;
+
+    fn world_id() -> WorldId {
+        WorldId::new_from(NonZeroU64::new(1).unwrap())
+    }
+
+    #[test]
+    fn test_builder_sets_explicit_timing() {
+        let context = FrameContext::builder(world_id())
+            .frame_number(7)
+            .delta_time_secs(0.025)
+            .fixed_time_secs(0.01)
+            .rng_seed(3)
+            .build();
+        assert_eq!(context.frame_number, 7);
+        assert_eq!(context.fixed_time_secs, 0.01);
+        assert_eq!(context.delta_time_secs, 0.025);
+        assert_eq!(context.rng_seed, 3);
+        let frame_time = context.current_frame_start - context.last_frame_start;
+        assert!((frame_time.as_secs_f32() - 0.025).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_builder_uses_injected_frame_starts() {
+        let last = std::time::Instant::now();
+        let current = last + std::time::Duration::from_millis(40);
+        let context = FrameContext::builder(world_id())
+            .frame_starts(last, current)
+            .build();
+        assert_eq!(context.last_frame_start, last);
+        assert_eq!(context.current_frame_start, current);
+    }
+
+    #[cfg(feature = "rand")]
+    fn run(seed: u64, frames: usize) -> Vec<u64> {
+        let mut context = FrameContext::builder(world_id()).rng_seed(seed).build();
+        let mut samples = Vec::new();
+        for _ in 0..frames {
+            context.frame_number += 1;
+            let mut rng = context.frame_rng();
+            samples.extend((0..3).map(|_| rng.next_u64()));
+        }
+        samples
+    }
+
+    #[test]
+    #[cfg(feature = "rand")]
+    fn test_same_seed_is_reproducible() {
+        assert_eq!(run(42, 5), run(42, 5));
+    }
+
+    #[test]
+    #[cfg(feature = "rand")]
+    fn test_sequences_differ_by_seed_and_frame() {
+        let samples = run(42, 2);
+        assert_ne!(samples[..3], samples[3..]);
+        assert_ne!(run(42, 2), run(43, 2));
+    }
 }
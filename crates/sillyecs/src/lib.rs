@@ -1,17 +1,27 @@
 //! # Utility functions for `sillyecs`.
 
+mod aligned_vec;
 mod entity_id;
 mod flatten_copy_slices;
 mod flatten_slices;
 mod flatten_slices_mut;
+mod flatten_zip;
 mod frame_context;
+mod storage;
 mod world;
 mod world_id;
+mod world_tag;
+mod zip_mut2;
 
+pub use aligned_vec::AlignedVec;
 pub use entity_id::EntityId;
 pub use flatten_copy_slices::FlattenCopySlices;
 pub use flatten_slices::FlattenSlices;
 pub use flatten_slices_mut::FlattenSlicesMut;
-pub use frame_context::FrameContext;
+pub use flatten_zip::FlattenZip;
+pub use frame_context::{FrameContext, FrameContextBuilder};
+pub use storage::ComponentStorage;
 pub use world::World;
 pub use world_id::WorldId;
+pub use world_tag::WorldTag;
+pub use zip_mut2::ZipMut2;
@@ -0,0 +1,183 @@
+use std::ops::{Deref, DerefMut};
+
+/// The storage of one component column of an archetype.
+///
+/// Generated archetypes keep one storage per component, backed by [`Vec`] unless the ECS
+/// schema selects a different `storage` type. Since archetypes derive `Debug`, `Default` and
+/// `Clone`, a custom storage should implement these as well.
+///
+/// # Contiguity
+/// A storage must dereference to a contiguous slice of its values, since systems receive each
+/// component column as a single slice. Custom storages can thus change how that slice is
+/// allocated, e.g. from a pool or an arena, but not split it: paged or chunked layouts cannot
+/// implement this trait.
+pub trait ComponentStorage<T>: Default + Deref<Target = [T]> + DerefMut {
+    /// Appends a value to the end of the storage.
+    fn push(&mut self, value: T);
+
+    /// Removes the value at `index` and returns it, replacing it with the last value.
+    ///
+    /// # Panics
+    /// Panics if `index` is out of bounds.
+    fn swap_remove(&mut self, index: usize) -> T;
+
+    /// Removes the last value and returns it, or [`None`] if the storage is empty.
+    #[inline]
+    fn pop(&mut self) -> Option<T> {
+        let len = self.len();
+        (len > 0).then(|| self.swap_remove(len - 1))
+    }
+
+    /// Reserves capacity for at least `additional` more values.
+    fn reserve(&mut self, additional: usize);
+
+    /// Shrinks the capacity of the storage as much as possible.
+    fn shrink_to_fit(&mut self);
+
+    /// Returns the number of values the storage can hold without reallocating.
+    fn capacity(&self) -> usize;
+
+    /// Returns the number of values in the storage.
+    #[inline]
+    fn len(&self) -> usize {
+        self.deref().len()
+    }
+
+    /// Returns `true` if the storage holds no values.
+    #[inline]
+    fn is_empty(&self) -> bool {
+        self.deref().is_empty()
+    }
+
+    /// Returns a reference to the value at `index`, or [`None`] if it is out of bounds.
+    #[inline]
+    fn get(&self, index: usize) -> Option<&T> {
+        self.deref().get(index)
+    }
+
+    /// Returns a mutable reference to the value at `index`, or [`None`] if it is out of bounds.
+    #[inline]
+    fn get_mut(&mut self, index: usize) -> Option<&mut T> {
+        self.deref_mut().get_mut(index)
+    }
+
+    /// Returns an iterator over the values.
+    #[inline]
+    fn iter(&self) -> std::slice::Iter<'_, T> {
+        self.deref().iter()
+    }
+
+    /// Returns an iterator over mutable references to the values.
+    #[inline]
+    fn iter_mut(&mut self) -> std::slice::IterMut<'_, T> {
+        self.deref_mut().iter_mut()
+    }
+}
+
+impl<T> ComponentStorage<T> for Vec<T> {
+    #[inline]
+    fn push(&mut self, value: T) {
+        Vec::push(self, value);
+    }
+
+    #[inline]
+    fn swap_remove(&mut self, index: usize) -> T {
+        Vec::swap_remove(self, index)
+    }
+
+    #[inline]
+    fn pop(&mut self) -> Option<T> {
+        Vec::pop(self)
+    }
+
+    #[inline]
+    fn reserve(&mut self, additional: usize) {
+        Vec::reserve(self, additional);
+    }
+
+    #[inline]
+    fn shrink_to_fit(&mut self) {
+        Vec::shrink_to_fit(self);
+    }
+
+    #[inline]
+    fn capacity(&self) -> usize {
+        Vec::capacity(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fill<S: ComponentStorage<u32>>(storage: &mut S) {
+        storage.reserve(3);
+        for value in [1, 2, 3] {
+            storage.push(value);
+        }
+    }
+
+    #[test]
+    fn test_vec_storage() {
+        let mut storage = Vec::new();
+        fill(&mut storage);
+        assert!(ComponentStorage::capacity(&storage) >= 3);
+        assert_eq!(ComponentStorage::len(&storage), 3);
+        assert_eq!(ComponentStorage::get(&storage, 1), Some(&2));
+        assert_eq!(ComponentStorage::swap_remove(&mut storage, 0), 1);
+        assert_eq!(storage, [3, 2]);
+        for value in ComponentStorage::iter_mut(&mut storage) {
+            *value *= 10;
+        }
+        assert_eq!(ComponentStorage::iter(&storage).sum::<u32>(), 50);
+    }
+
+    #[derive(Debug, Default)]
+    struct Column(Vec<u32>);
+
+    impl Deref for Column {
+        type Target = [u32];
+
+        fn deref(&self) -> &[u32] {
+            &self.0
+        }
+    }
+
+    impl DerefMut for Column {
+        fn deref_mut(&mut self) -> &mut [u32] {
+            &mut self.0
+        }
+    }
+
+    impl ComponentStorage<u32> for Column {
+        fn push(&mut self, value: u32) {
+            self.0.push(value);
+        }
+
+        fn swap_remove(&mut self, index: usize) -> u32 {
+            self.0.swap_remove(index)
+        }
+
+        fn reserve(&mut self, additional: usize) {
+            self.0.reserve(additional);
+        }
+
+        fn shrink_to_fit(&mut self) {
+            self.0.shrink_to_fit();
+        }
+
+        fn capacity(&self) -> usize {
+            self.0.capacity()
+        }
+    }
+
+    #[test]
+    fn test_default_pop() {
+        let mut storage = Column::default();
+        fill(&mut storage);
+        assert_eq!(storage.pop(), Some(3));
+        assert_eq!(storage.pop(), Some(2));
+        assert_eq!(storage.pop(), Some(1));
+        assert_eq!(storage.pop(), None);
+    }
+}
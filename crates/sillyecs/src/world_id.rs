@@ -15,6 +15,7 @@ impl WorldId {
     /// # Implementation
     /// This function uses a thread-safe counter with sequential consistency ordering
     /// to ensure unique IDs even under concurrent access.
+    #[allow(clippy::new_without_default)]
     pub fn new() -> Self {
         static WORLD_IDS: AtomicU64 = AtomicU64::new(1);
         let id = WORLD_IDS.fetch_add(1, core::sync::atomic::Ordering::SeqCst);
@@ -0,0 +1,72 @@
+/// Identifies a single world instance, e.g. to tell a world apart from its fork.
+///
+/// Unlike the [`WorldId`](crate::WorldId), which is shared by all worlds of the same type, every
+/// world instance gets its own tag. With the `world-tag` feature enabled, the entity IDs it hands
+/// out carry it in debug builds. Otherwise, the tag is a zero-sized type and entity IDs carry no
+/// tag.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct WorldTag(
+    #[cfg(all(feature = "world-tag", debug_assertions))] pub(crate) core::num::NonZeroU32,
+);
+
+impl WorldTag {
+    /// Returns a new world tag.
+    ///
+    /// In debug builds with the `world-tag` feature enabled, tags are taken from a monotonically
+    /// increasing atomic counter, starting from 1. Should the counter ever wrap, tags start over at
+    /// 1, which merely weakens the checks.
+    pub fn new() -> Self {
+        #[cfg(all(feature = "world-tag", debug_assertions))]
+        {
+            static WORLD_TAGS: core::sync::atomic::AtomicU32 =
+                core::sync::atomic::AtomicU32::new(1);
+            let tag = WORLD_TAGS.fetch_add(1, core::sync::atomic::Ordering::Relaxed);
+            Self(core::num::NonZeroU32::new(tag).unwrap_or(core::num::NonZeroU32::MIN))
+        }
+        #[cfg(not(all(feature = "world-tag", debug_assertions)))]
+        {
+            Self()
+        }
+    }
+}
+
+impl Default for WorldTag {
+    /// Returns a new world tag, see [`WorldTag::new`].
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::EntityId;
+
+    #[test]
+    fn test_untagged_ids_belong_to_any_world() {
+        let id = EntityId::new();
+        assert_eq!(id.world_tag(), None);
+        id.debug_assert_world(WorldTag::new(), "First");
+        id.debug_assert_world(WorldTag::new(), "Second");
+    }
+
+    #[test]
+    #[cfg(all(feature = "world-tag", debug_assertions))]
+    fn test_tags_do_not_affect_identity() {
+        let world = WorldTag::new();
+        let id = EntityId::new();
+        let tagged = id.with_world_tag(world);
+        assert_eq!(tagged.world_tag(), Some(world));
+        assert_eq!(tagged, id);
+        assert_ne!(WorldTag::new(), world);
+        tagged.debug_assert_world(world, "First");
+    }
+
+    #[test]
+    #[cfg(all(feature = "world-tag", debug_assertions))]
+    #[should_panic(expected = "but was used with the `Second` world instance")]
+    fn test_foreign_ids_panic_in_debug() {
+        let id = EntityId::new().with_world_tag(WorldTag::new());
+        id.debug_assert_world(WorldTag::new(), "Second");
+    }
+}
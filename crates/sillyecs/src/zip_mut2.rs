@@ -0,0 +1,91 @@
+use std::iter::FusedIterator;
+use std::slice::IterMut;
+
+/// A mutable iterator over two slices of equal length.
+///
+/// Yields a mutable reference into each slice per step, e.g. to update two different
+/// components of the same entity at once.
+pub struct ZipMut2<'a, A, B> {
+    a: IterMut<'a, A>,
+    b: IterMut<'a, B>,
+}
+
+impl<'a, A, B> ZipMut2<'a, A, B> {
+    /// Creates the iterator.
+    ///
+    /// Both slices are expected to have the same length; in debug builds, a mismatch panics.
+    /// In release builds, iteration stops at the end of the shorter slice.
+    pub fn new(a: &'a mut [A], b: &'a mut [B]) -> Self {
+        debug_assert_eq!(a.len(), b.len(), "zipped slices must have equal lengths");
+        Self {
+            a: a.iter_mut(),
+            b: b.iter_mut(),
+        }
+    }
+}
+
+impl<'a, A, B> Iterator for ZipMut2<'a, A, B> {
+    type Item = (&'a mut A, &'a mut B);
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        Some((self.a.next()?, self.b.next()?))
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.a.len().min(self.b.len());
+        (len, Some(len))
+    }
+}
+
+impl<'a, A, B> ExactSizeIterator for ZipMut2<'a, A, B> {}
+impl<'a, A, B> FusedIterator for ZipMut2<'a, A, B> {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_forward() {
+        let a = &mut [1, 2, 3][..];
+        let b = &mut ['a', 'b', 'c'][..];
+        let mut iter = ZipMut2::new(a, b);
+        assert_eq!(iter.len(), 3);
+        assert_eq!(iter.next(), Some((&mut 1, &mut 'a')));
+        assert_eq!(iter.len(), 2);
+        assert_eq!(iter.next(), Some((&mut 2, &mut 'b')));
+        assert_eq!(iter.next(), Some((&mut 3, &mut 'c')));
+        assert_eq!(iter.next(), None);
+        assert_eq!(iter.next(), None);
+    }
+
+    #[test]
+    fn test_mutation() {
+        let mut a = [1, 2, 3];
+        let mut b = [10, 20, 30];
+        for (x, y) in ZipMut2::new(&mut a, &mut b) {
+            std::mem::swap(x, y);
+        }
+        assert_eq!(a, [10, 20, 30]);
+        assert_eq!(b, [1, 2, 3]);
+    }
+
+    #[test]
+    fn test_empty() {
+        let a: &mut [u8] = &mut [];
+        let b: &mut [u16] = &mut [];
+        let mut iter = ZipMut2::new(a, b);
+        assert_eq!(iter.len(), 0);
+        assert_eq!(iter.next(), None);
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic(expected = "zipped slices must have equal lengths")]
+    fn test_length_mismatch() {
+        let a = &mut [1, 2][..];
+        let b = &mut [1][..];
+        let _ = ZipMut2::new(a, b);
+    }
+}